@@ -0,0 +1,284 @@
+#[macro_use]
+extern crate tracing;
+
+use std::{path::PathBuf, process::ExitCode, time::Duration};
+
+use clap::{Parser, Subcommand};
+use haunt_core::{
+    friends,
+    lobby::{self, ShortMatchData},
+    local::presence,
+    login::{Login, LoginFail},
+    source::Backend,
+    streamer::StreamerMask,
+    traffic,
+    watcher::{Change, LobbyWatcher},
+    Client,
+};
+use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+
+mod table;
+
+/// Shows who's in your Valorant match without running the app.
+///
+/// Exits with 2, 3 or 4 if login fails at the lockfile, entitlements or session stage.
+#[derive(Parser)]
+#[command(name = "haunt", version)]
+struct Cli {
+    /// Keep ValAPI content and name/MMR responses here between runs
+    #[arg(long, global = true, env = "HAUNT_CACHE_DIR")]
+    cache_dir: Option<PathBuf>,
+    /// Use the app's settings.json, for poll intervals, rank history and region overrides
+    #[arg(long, global = true, env = "HAUNT_SETTINGS")]
+    settings: Option<PathBuf>,
+    /// Print JSON instead of a table
+    #[arg(long, global = true)]
+    json: bool,
+    /// Record local and pd/glz traffic to a redacted file in this directory, for bug reports
+    #[arg(long, global = true, env = "HAUNT_RECORD", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+    /// Answer requests from a recording instead of the running game
+    #[arg(long, global = true, env = "HAUNT_REPLAY")]
+    replay: Option<PathBuf>,
+    /// Show a made up lobby that loops through a match, instead of the running game
+    #[arg(long, global = true, conflicts_with = "replay")]
+    demo: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the current lobby
+    Match,
+    /// Print a player's rank in the last few acts
+    Mmr { puuid: String },
+    /// Print what your friends are doing
+    Friends,
+    /// Reprint the lobby every time it changes
+    Watch {
+        /// Seconds between polls. Defaults to the app's, which poll faster during agent select
+        #[arg(long)]
+        interval: Option<u64>,
+    },
+}
+
+#[derive(Debug, thiserror::Error)]
+enum Error {
+    #[error(transparent)]
+    Login(#[from] LoginFail),
+    #[error("Not in a match.")]
+    NoMatch,
+    #[error(transparent)]
+    Other(#[from] color_eyre::Report),
+}
+
+impl Error {
+    fn exit_code(&self) -> u8 {
+        match self {
+            Error::Login(LoginFail::Lockfile) => 2,
+            Error::Login(LoginFail::Entitlements) => 3,
+            Error::Login(LoginFail::Session) => 4,
+            Error::NoMatch | Error::Other(_) => 1,
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    // logs go to stderr so they don't get mixed into the output
+    tracing_subscriber::registry()
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(EnvFilter::from_default_env())
+        .init();
+
+    let cli = Cli::parse();
+
+    match run(cli).await {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(why) => {
+            eprintln!("{why}");
+            ExitCode::from(why.exit_code())
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Error> {
+    if let Some(dir) = &cli.record {
+        let path = traffic::start_recording(dir)?;
+        eprintln!("Recording to {}", path.display());
+    }
+
+    let client = Client::new()?;
+    // loaded first, since content is fetched in the language it picks
+    if let Some(path) = cli.settings {
+        client.settings.load(path);
+    }
+    client.load_cache(cli.cache_dir.as_deref()).await;
+    match (cli.replay, cli.demo) {
+        (Some(recording), _) => client.source.set(Backend::Replay { recording })?,
+        (None, true) => client.source.set(Backend::Demo)?,
+        (None, false) => client
+            .source
+            .set(client.settings.get().data_source.clone())?,
+    }
+
+    // only lives as long as the command, so aliases are only stable within a watch
+    let mask = StreamerMask::default();
+
+    match cli.command {
+        Command::Match => {
+            let login = client.source.get().login(&client).await?;
+            mask.set_user(Some(login.session.puuid.clone()));
+            let lobby = find_lobby(&client, &login).await?;
+            print_lobby(&client, &mask, lobby, cli.json, true)?;
+        }
+        Command::Mmr { puuid } => {
+            let login = client.source.get().login(&client).await?;
+            print_mmr(&client, &login, &puuid, cli.json).await?;
+        }
+        Command::Friends => {
+            let login = client.source.get().login(&client).await?;
+            let mut friends = friends::load(&client, &login.lockfile).await?;
+
+            let privacy = &client.settings.get().privacy;
+            if privacy.streamer_mode {
+                mask.set_user(Some(login.session.puuid.clone()));
+                for friend in &mut friends {
+                    if let Some(name) = mask.name(&friend.puuid, privacy.hide_own_name) {
+                        friend.name = name;
+                    }
+                }
+            }
+
+            let text = match cli.json {
+                true => serde_json::to_string_pretty(&friends).map_err(color_eyre::Report::from)?,
+                false => table::friends(&friends),
+            };
+            println!("{}", text.trim_end());
+        }
+        Command::Watch { interval } => {
+            watch(&client, &mask, cli.json, interval.map(Duration::from_secs)).await;
+        }
+    }
+
+    Ok(())
+}
+
+async fn get_presences(client: &Client, login: &Login) -> Result<Vec<presence::Player>, Error> {
+    client
+        .source
+        .get()
+        .presences(client, &login.lockfile)
+        .await
+        .map_err(|why| {
+            error!("Unable to load player presences: {why}");
+            Error::Login(LoginFail::Session)
+        })
+}
+
+async fn find_match_id(client: &Client, login: &Login) -> Option<String> {
+    client
+        .source
+        .get()
+        .find_match(client, &login.entitlements, &login.session)
+        .await
+}
+
+/// Loads the match the user is in, with every player filled in.
+async fn find_lobby(client: &Client, login: &Login) -> Result<ShortMatchData, Error> {
+    let presences = get_presences(client, login).await?;
+    let match_id = find_match_id(client, login).await.ok_or(Error::NoMatch)?;
+
+    // nothing is shown until the whole lobby is loaded, so individual updates aren't needed
+    let lobby = lobby::load(
+        client,
+        &login.entitlements,
+        &login.session,
+        &match_id,
+        &presences,
+        |_| (),
+    )
+    .await?;
+
+    Ok(lobby)
+}
+
+/// Prints the lobby, masked if streamer mode is on.
+///
+/// * `pretty` - Pretty print JSON. Watch prints one lobby per line instead, so it can be piped.
+fn print_lobby(
+    client: &Client,
+    mask: &StreamerMask,
+    mut lobby: ShortMatchData,
+    json: bool,
+    pretty: bool,
+) -> Result<(), Error> {
+    let privacy = &client.settings.get().privacy;
+    if privacy.streamer_mode {
+        mask.lobby(&mut lobby, privacy.hide_own_name, |_| ());
+    }
+
+    let text = match (json, pretty) {
+        (true, true) => serde_json::to_string_pretty(&lobby).map_err(color_eyre::Report::from)?,
+        (true, false) => serde_json::to_string(&lobby).map_err(color_eyre::Report::from)?,
+        (false, _) => table::lobby(&lobby, &client.content.get()),
+    };
+    println!("{}", text.trim_end());
+
+    Ok(())
+}
+
+async fn print_mmr(client: &Client, login: &Login, puuid: &str, json: bool) -> Result<(), Error> {
+    let source = client.source.get();
+    let seasons = source.seasons(client).await?;
+    // same as the lobby
+    let acts = &seasons[..seasons.len().min(client.settings.get().rank_history_acts)];
+
+    let history = source
+        .mmr(client, &login.entitlements, &login.session, puuid, acts)
+        .await?;
+
+    if client.source.backend() == Backend::Live {
+        if let Err(why) = client.response_cache.flush() {
+            warn!("Unable to save the response cache: {why}");
+        }
+    }
+
+    let content = client.content.get();
+    let ranks = lobby::rank_history(&history, &content.competitive_tiers);
+    let text = match json {
+        true => serde_json::to_string_pretty(&ranks).map_err(color_eyre::Report::from)?,
+        false => table::ranks(&ranks, &content),
+    };
+    println!("{}", text.trim_end());
+
+    Ok(())
+}
+
+/// Polls the client forever, printing the lobby whenever a match starts or changes.
+/// State changes and login failures go to stderr.
+async fn watch(client: &Client, mask: &StreamerMask, json: bool, interval: Option<Duration>) {
+    let mut watcher = LobbyWatcher::default();
+    let intervals = client.settings.get().poll_intervals.clone();
+
+    loop {
+        let changes = watcher.tick(client).await;
+        mask.set_user(watcher.user().map(str::to_string));
+
+        for change in changes {
+            match change {
+                Change::State { to, .. } => eprintln!("Client is now {to:?}."),
+                Change::LoginFailed(fail) => eprintln!("{fail}"),
+                Change::Loaded(lobby) | Change::Updated(lobby) => {
+                    if let Err(why) = print_lobby(client, mask, lobby, json, false) {
+                        warn!("Unable to print lobby: {why}");
+                    }
+                }
+            }
+        }
+
+        let interval = interval.unwrap_or_else(|| intervals.for_state(watcher.state()));
+        tokio::time::sleep(interval).await;
+    }
+}
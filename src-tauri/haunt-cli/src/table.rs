@@ -0,0 +1,138 @@
+use haunt_core::{
+    friends::FriendStatus,
+    lobby::ShortMatchData,
+    pvp::matchdata::Role,
+    valapi::{
+        content::Content,
+        seasons::{self, CompetitiveTier},
+    },
+};
+
+/// Lays out rows as left-aligned columns, each as wide as its widest cell.
+fn render<const N: usize>(headers: [&str; N], rows: Vec<[String; N]>) -> String {
+    let mut widths = headers.map(|h| h.chars().count());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: Vec<&str>| {
+        let padded = cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:width$}"))
+            .collect::<Vec<_>>();
+        padded.join("  ").trim_end().to_string() + "\n"
+    };
+
+    let mut table = line(headers.to_vec());
+    for row in &rows {
+        table += &line(row.iter().map(String::as_str).collect());
+    }
+
+    table
+}
+
+pub fn lobby(lobby: &ShortMatchData, content: &Content) -> String {
+    let map = content
+        .maps
+        .iter()
+        .find(|m| m.map_url == lobby.map)
+        .map_or(lobby.map.as_str(), |m| m.display_name.as_str());
+
+    let players = lobby.participants().collect::<Vec<_>>();
+    let parties = lobby.parties();
+
+    let rows = players
+        .iter()
+        .map(|player| {
+            [
+                match lobby.team_based {
+                    true => player.team.clone(),
+                    false => String::new(),
+                },
+                player.name.clone(),
+                player
+                    .character
+                    .as_ref()
+                    .map(|a| a.display_name.clone())
+                    .unwrap_or_default(),
+                match player.role {
+                    Role::Player => String::new(),
+                    role => format!("{role:?}").to_lowercase(),
+                },
+                player
+                    .account_level
+                    .map(|l| l.to_string())
+                    .unwrap_or_default(),
+                parties
+                    .get(&player.party_id)
+                    .map(|p| p.to_string())
+                    .unwrap_or_default(),
+                player
+                    .rank_history
+                    .iter()
+                    .map(|r| r.tier_name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(" / "),
+            ]
+        })
+        .collect();
+
+    let headers = ["Team", "Name", "Agent", "Role", "Level", "Party", "Ranks"];
+    format!("{map} - {}\n\n{}", lobby.mode, render(headers, rows))
+}
+
+/// Most recent act first.
+pub fn ranks(ranks: &[CompetitiveTier], content: &Content) -> String {
+    if ranks.is_empty() {
+        return String::from("No competitive games in the last few acts.\n");
+    }
+
+    let rows = ranks
+        .iter()
+        .map(|rank| {
+            [
+                seasons::act_name(&content.seasons, &rank.act),
+                rank.tier_name.clone(),
+            ]
+        })
+        .collect();
+
+    render(["Act", "Rank"], rows)
+}
+
+pub fn friends(friends: &[FriendStatus]) -> String {
+    if friends.is_empty() {
+        return String::from("No friends yet.\n");
+    }
+
+    let rows = friends
+        .iter()
+        .map(|friend| {
+            [
+                friend.name.clone(),
+                format!("{:?}", friend.availability).to_lowercase(),
+                format!("{:?}", friend.state).to_lowercase(),
+                friend.mode.clone().unwrap_or_default(),
+                friend.map.clone().unwrap_or_default(),
+                friend
+                    .score
+                    .map(|s| format!("{}-{}", s.ally, s.enemy))
+                    .unwrap_or_default(),
+                friend.party_size.map(|s| s.to_string()).unwrap_or_default(),
+                friend
+                    .rank
+                    .as_ref()
+                    .map(|r| r.tier_name.clone())
+                    .unwrap_or_default(),
+            ]
+        })
+        .collect();
+
+    let headers = [
+        "Name", "Status", "State", "Mode", "Map", "Score", "Party", "Rank",
+    ];
+    render(headers, rows)
+}
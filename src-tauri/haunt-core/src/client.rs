@@ -0,0 +1,75 @@
+use std::path::Path;
+
+use color_eyre::eyre::Result;
+
+use crate::{
+    pvp::{cache::ResponseCache, ratelimit::RateLimiter},
+    settings::SettingsStore,
+    source::SourceStore,
+    valapi::content::{self, ContentStore},
+};
+
+/// Shared state for every request Haunt makes. Build one and pass it around.
+#[derive(Debug, Default)]
+pub struct Client {
+    pub http: reqwest::Client,
+    /// used for local cnx with tls disabled
+    pub offline_http: reqwest::Client,
+    /// shared by every pd/glz request
+    pub rate_limiter: RateLimiter,
+    /// name and mmr lookups. only persisted once `ResponseCache::load` is called
+    pub response_cache: ResponseCache,
+    /// ValAPI content. empty until it's loaded from disk or refreshed
+    pub content: ContentStore,
+    /// where lobbies come from. live unless switched
+    pub source: SourceStore,
+    /// defaults until `SettingsStore::load` is called
+    pub settings: SettingsStore,
+}
+
+impl Client {
+    pub fn new() -> Result<Self> {
+        let http = reqwest::Client::builder().build()?;
+
+        // the local client api uses a self signed cert
+        let offline_http = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .build()?;
+
+        Ok(Client {
+            http,
+            offline_http,
+            ..Default::default()
+        })
+    }
+
+    /// Builds a client with [`Client::load_cache`].
+    pub async fn with_cache(cache_dir: Option<&Path>) -> Result<Self> {
+        let client = Client::new()?;
+        client.load_cache(cache_dir).await;
+
+        Ok(client)
+    }
+
+    /// Loads whatever was cached in `cache_dir` last run, then refreshes ValAPI content. The layout
+    /// matches the app's data directory, so the two can share it. Nothing is saved if `cache_dir`
+    /// is None. Content is fetched in the language setting's language, so load settings first.
+    pub async fn load_cache(&self, cache_dir: Option<&Path>) {
+        let content_dir = cache_dir.map(|dir| dir.join("ValAPI"));
+
+        if let Some(dir) = cache_dir {
+            self.response_cache.load(dir.join("response_cache.json"));
+        }
+        if let Some(dir) = &content_dir {
+            match content::load_from_disk(dir) {
+                Ok(content) => self.content.set(content),
+                Err(why) => debug!("No usable ValAPI content cache: {why}"),
+            }
+        }
+
+        // agents and ranks fall back to UUIDs without content, which is still better than nothing
+        if let Err(why) = self.refresh_content(content_dir.as_deref()).await {
+            warn!("Unable to refresh ValAPI content: {why}");
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! What the user's friends are up to, put together from their friends list and presences.
+
+use color_eyre::Result;
+use serde::Serialize;
+
+use crate::{
+    local::{
+        friends::Friend,
+        presence::{self, Availability},
+    },
+    lockfile,
+    valapi::{
+        content::Content,
+        seasons::{self, CompetitiveTier, Season},
+    },
+    watcher::{self, ClientState},
+    Client,
+};
+
+/// A friend and what they're doing in Valorant.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FriendStatus {
+    pub puuid: String,
+    pub name: String,
+    pub availability: Availability,
+    /// Offline unless they're playing Valorant, even if they're online in another game
+    pub state: ClientState,
+    /// map display name, while they're in a match
+    pub map: Option<String>,
+    /// the queue they're in or playing, e.g. Competitive
+    pub mode: Option<String>,
+    /// only while they're ingame
+    pub score: Option<Score>,
+    pub party_size: Option<u32>,
+    /// their rank this act, as their presence shows it
+    pub rank: Option<CompetitiveTier>,
+}
+
+/// Rounds won by each team, from the friend's side.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Score {
+    pub ally: u32,
+    pub enemy: u32,
+}
+
+impl FriendStatus {
+    /// * `current_act` - The act ranks in presences are from.
+    fn new(friend: Friend, current_act: Option<&Season>, content: &Content) -> Self {
+        let name = format!("{} #{}", friend.game_name, friend.game_tag);
+        let Some(presence) = &friend.presence else {
+            return FriendStatus {
+                puuid: friend.puuid,
+                name,
+                availability: friend.availability,
+                state: ClientState::Offline,
+                map: None,
+                mode: None,
+                score: None,
+                party_size: None,
+                rank: None,
+            };
+        };
+
+        let private = &presence.private;
+        let state = watcher::state_from_presence(presence);
+        let in_match = matches!(state, ClientState::Pregame | ClientState::Ingame);
+
+        let map = (in_match && !private.match_map.is_empty()).then(|| {
+            content
+                .maps
+                .iter()
+                .find(|m| m.map_url == private.match_map)
+                .map_or_else(|| private.match_map.clone(), |m| m.display_name.clone())
+        });
+
+        // presences don't say which act the tier is from, so it's taken to be the current one
+        let episode = current_act.map_or("", |act| act.competitive_tiers_uuid.as_str());
+        let rank = CompetitiveTier {
+            act: current_act
+                .map(|act| act.season_uuid.clone())
+                .unwrap_or_default(),
+            ..CompetitiveTier::from_act_tier(
+                &content.competitive_tiers,
+                episode,
+                private.competitive_tier,
+            )
+        };
+
+        FriendStatus {
+            puuid: friend.puuid,
+            name,
+            availability: friend.availability,
+            state,
+            map,
+            mode: (!private.queue_id.is_empty()).then(|| presence::queue_name(&private.queue_id)),
+            score: (state == ClientState::Ingame).then_some(Score {
+                ally: private.party_owner_match_score_ally_team,
+                enemy: private.party_owner_match_score_enemy_team,
+            }),
+            party_size: (private.party_size > 0).then_some(private.party_size),
+            rank: Some(rank),
+        }
+    }
+}
+
+/// Loads the friends list and what everyone on it is doing, online friends first.
+pub async fn load(client: &Client, lockfile: &lockfile::Config) -> Result<Vec<FriendStatus>> {
+    let friends = client.source.get().friends(client, lockfile).await?;
+    let content = client.content.get();
+    let current_act = seasons::current_act(&content.seasons);
+
+    let mut friends = friends
+        .into_iter()
+        .map(|friend| FriendStatus::new(friend, current_act, &content))
+        .collect::<Vec<_>>();
+    friends.sort_by_cached_key(|f| {
+        (
+            f.availability == Availability::Offline,
+            f.state == ClientState::Offline,
+            f.name.to_lowercase(),
+        )
+    });
+
+    Ok(friends)
+}
+
+/// A friend moving between menus, queue, agent select and matches, or starting or quitting
+/// Valorant.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FriendChange {
+    pub puuid: String,
+    pub name: String,
+    pub from: ClientState,
+    pub to: ClientState,
+    /// the map they're playing or just left
+    pub map: Option<String>,
+}
+
+/// What changed between two loads of the friends list. Friends who were added or removed in
+/// between aren't changes.
+pub fn changes(previous: &[FriendStatus], current: &[FriendStatus]) -> Vec<FriendChange> {
+    current
+        .iter()
+        .filter_map(|friend| {
+            let before = previous.iter().find(|p| p.puuid == friend.puuid)?;
+            (before.state != friend.state).then(|| FriendChange {
+                puuid: friend.puuid.clone(),
+                name: friend.name.clone(),
+                from: before.state,
+                to: friend.state,
+                map: friend.map.clone().or_else(|| before.map.clone()),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,22 @@
+//! Everything Haunt needs to talk to the Riot client, the pd/glz services and ValAPI.
+//! Nothing in here depends on Tauri unless the `tauri` feature is enabled, so tools can use
+//! it directly through a [`Client`].
+
+#[macro_use]
+extern crate tracing;
+
+mod client;
+pub mod friends;
+pub mod lobby;
+pub mod local;
+pub mod lockfile;
+pub mod login;
+pub mod pvp;
+pub mod settings;
+pub mod source;
+pub mod streamer;
+pub mod traffic;
+pub mod valapi;
+pub mod watcher;
+
+pub use client::Client;
@@ -0,0 +1,569 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use color_eyre::{eyre::eyre, Result};
+use futures::StreamExt;
+use serde::Serialize;
+
+use crate::{
+    local::{entitlements, presence, sessions},
+    pvp::{
+        matchdata::{FieldStatus, Layout, MatchData, Player, PlayerStatus, Role},
+        mmr::{History, NoCompetitiveHistory, SeasonalInfo},
+    },
+    source::Backend,
+    valapi::{
+        agents::Agent,
+        seasons::{CompetitiveTier, Season},
+    },
+    Client,
+};
+
+/// How many per-player requests can be in flight at once while loading a match.
+const MAX_CONCURRENT_REQUESTS: usize = 4;
+
+/// Counts down the pieces of data each player is still waiting on.
+struct LoadProgress(Mutex<HashMap<String, usize>>);
+
+impl LoadProgress {
+    fn new(players: &[&Player], pieces: usize) -> Self {
+        let remaining = players.iter().map(|p| (p.puuid.clone(), pieces)).collect();
+        LoadProgress(Mutex::new(remaining))
+    }
+
+    /// Marks one piece of data as loaded for the player, returning their new load state.
+    fn advance(&self, puuid: &str) -> LoadState {
+        let mut remaining = self.0.lock().unwrap();
+        let left = remaining.entry(puuid.to_string()).or_insert(1);
+        *left = left.saturating_sub(1);
+
+        match *left {
+            0 => LoadState::Loaded,
+            _ => LoadState::Loading,
+        }
+    }
+}
+
+/// Picks the player's name from the name service, falling back to their presence.
+fn resolve_name(
+    player: &Player,
+    names: &color_eyre::Result<HashMap<String, String>>,
+    presences: &[presence::Player],
+) -> (Option<String>, FieldStatus) {
+    if let Ok(names) = names {
+        if let Some(name) = names.get(&player.puuid) {
+            return (Some(name.clone()), FieldStatus::Loaded);
+        }
+    }
+
+    if let Some(presence) = presences.iter().find(|p| p.puuid == player.puuid) {
+        let name = format!("{} #{}", presence.game_name, presence.game_tag);
+        return (Some(name), FieldStatus::Fallback);
+    }
+
+    match names {
+        Ok(_) => (None, FieldStatus::Unavailable),
+        Err(why) => (None, FieldStatus::Error(why.to_string())),
+    }
+}
+
+/// Builds a history with only the current act from the player's presence, for when the MMR
+/// service fails. Only works for players we can see presences for (party members and friends).
+///
+/// * `current_act` - None if seasons failed to load, since there's no telling which act the
+///   presence's rank is from
+fn presence_history(
+    puuid: &str,
+    presences: &[presence::Player],
+    current_act: Option<&Season>,
+) -> Option<History> {
+    let presence = presences.iter().find(|p| p.puuid == puuid)?;
+    let act = current_act?;
+
+    Some(vec![SeasonalInfo {
+        episode_id: act.competitive_tiers_uuid.clone(),
+        season_id: act.season_uuid.clone(),
+        competitive_tier: presence.private.competitive_tier,
+    }])
+}
+
+/// Fills in names and competitive history for everyone in the match, coaches and observers included.
+/// Each player's data is passed to `on_update` as soon as it arrives.
+///
+/// None of this is required to show the match, so failures are recorded in each player's
+/// [`PlayerStatus`] instead of being returned. Presences are used to fill gaps where possible.
+pub async fn fill_player_info(
+    client: &Client,
+    match_data: &mut MatchData,
+    presences: &[presence::Player],
+    entitlements_config: &entitlements::Config,
+    session_config: &sessions::Config,
+    on_update: impl Fn(PlayerUpdate),
+) {
+    let source = client.source.get();
+    let source = source.as_ref();
+    let settings = client.settings.get();
+    let enrichments = &settings.enrichments;
+
+    let seasons = match enrichments.rank_history {
+        true => source.seasons(client).await.map_err(|why| {
+            warn!(
+                "Unable to load seasons: {:#?}. Ranks will fall back to presences.",
+                why
+            );
+            why.to_string()
+        }),
+        false => Ok(Vec::new()),
+    };
+    let acts = seasons
+        .as_ref()
+        .map(|seasons| &seasons[..seasons.len().min(settings.rank_history_acts)])
+        .map_err(|why| why.as_str());
+    // seasons are sorted most recent first
+    let current_act = seasons.as_ref().ok().and_then(|seasons| seasons.first());
+
+    if let Ok(acts) = acts {
+        debug!(
+            "Acts to load history for: {:#?}",
+            acts.iter().map(|s| &s.season_uuid).collect::<Vec<_>>()
+        );
+    }
+
+    let content = client.content.get();
+    let tiers = &content.competitive_tiers;
+    let on_update = &on_update;
+    let match_id = match_data.match_id.clone();
+
+    // names come from a single batch request, so they load alongside the per-player requests
+    let (mut names, mut histories) = {
+        let participants = match_data.participants().collect::<Vec<_>>();
+        let progress = LoadProgress::new(&participants, 2);
+
+        let names = async {
+            // None if names are turned off in settings
+            let names = match enrichments.names {
+                true => Some(
+                    source
+                        .names(client, entitlements_config, session_config, &participants)
+                        .await,
+                ),
+                false => None,
+            };
+
+            if let Some(Err(why)) = &names {
+                warn!("Unable to load player names: {why}. Falling back to presences.");
+            }
+
+            participants
+                .iter()
+                .map(|player| {
+                    let (name, status) = match &names {
+                        Some(names) => resolve_name(player, names, presences),
+                        None => (None, FieldStatus::Disabled),
+                    };
+
+                    on_update(PlayerUpdate {
+                        match_id: match_id.clone(),
+                        uuid: player.puuid.clone(),
+                        // incognito players keep their agent name from the skeleton
+                        name: name.clone().filter(|_| !player.is_incognito()),
+                        name_status: Some(status.clone()),
+                        rank_history: None,
+                        rank_status: None,
+                        load_state: progress.advance(&player.puuid),
+                    });
+
+                    (player.puuid.clone(), (name, status))
+                })
+                .collect::<HashMap<_, _>>()
+        };
+
+        // collected first, since a borrowing iterator held across the awaits makes the future !Send
+        let puuids = participants
+            .iter()
+            .map(|p| p.puuid.clone())
+            .collect::<Vec<_>>();
+        let histories = futures::stream::iter(puuids)
+            .map(|puuid| async move {
+                if !enrichments.rank_history {
+                    return (puuid, None);
+                }
+
+                let history = match acts {
+                    Ok(acts) => {
+                        info!("Filling history for player {}", puuid);
+                        source
+                            .mmr(client, entitlements_config, session_config, &puuid, acts)
+                            .await
+                    }
+                    Err(why) => Err(eyre!("Unable to load seasons: {why}")),
+                };
+
+                (puuid, Some(history))
+            })
+            .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+            .map(|(puuid, history)| {
+                let (history, status) = match history {
+                    None => (Vec::new(), FieldStatus::Disabled),
+                    Some(Ok(history)) => (history, FieldStatus::Loaded),
+                    Some(Err(why)) => {
+                        warn!("Unable to load history for {puuid}: {why}");
+                        match presence_history(&puuid, presences, current_act) {
+                            Some(history) => (history, FieldStatus::Fallback),
+                            // never having played competitive isn't a failure
+                            None if why.is::<NoCompetitiveHistory>() => {
+                                (Vec::new(), FieldStatus::Unavailable)
+                            }
+                            None => (Vec::new(), FieldStatus::Error(why.to_string())),
+                        }
+                    }
+                };
+
+                on_update(PlayerUpdate {
+                    match_id: match_id.clone(),
+                    uuid: puuid.clone(),
+                    name: None,
+                    name_status: None,
+                    rank_history: Some(rank_history(&history, tiers)),
+                    rank_status: Some(status.clone()),
+                    load_state: progress.advance(&puuid),
+                });
+
+                (puuid, (history, status))
+            })
+            .collect::<HashMap<_, _>>();
+
+        futures::join!(names, histories)
+    };
+
+    for player in match_data.participants_mut() {
+        if let Some((name, status)) = names.remove(&player.puuid) {
+            if let Some(name) = name {
+                player.set_name(name);
+            }
+            player.status.name = status;
+        }
+
+        if let Some((history, status)) = histories.remove(&player.puuid) {
+            player.competitive_history = history;
+            player.status.rank_history = status;
+        }
+    }
+
+    // other sources never touch the cache, so there's nothing of theirs to save
+    if client.source.backend() == Backend::Live {
+        if let Err(why) = client.response_cache.flush() {
+            warn!("Unable to save the response cache: {why}");
+        }
+    }
+}
+
+/// Loads the match from scratch, with every player filled in.
+/// Use [`quick_update`] to refresh it afterwards.
+pub async fn load<X: Default>(
+    client: &Client,
+    entitlements_config: &entitlements::Config,
+    session_config: &sessions::Config,
+    match_id: &str,
+    presences: &[presence::Player],
+    on_update: impl Fn(PlayerUpdate),
+) -> Result<ShortMatchData<X>> {
+    let mut match_data = client
+        .source
+        .get()
+        .match_info(
+            client,
+            entitlements_config,
+            session_config,
+            match_id,
+            presences,
+        )
+        .await?;
+
+    fill_player_info(
+        client,
+        &mut match_data,
+        presences,
+        entitlements_config,
+        session_config,
+        on_update,
+    )
+    .await;
+
+    let content = client.content.get();
+    Ok(ShortMatchData::from_match_data(
+        &match_data,
+        &content.agents,
+        &content.competitive_tiers,
+        LoadState::Loaded,
+    ))
+}
+
+/// Refetches agent selection, teams and parties for an already loaded match.
+/// Names and ranks can't change mid-match, so they aren't refetched.
+pub async fn quick_update<X>(
+    client: &Client,
+    lobby: &mut ShortMatchData<X>,
+    entitlements_config: &entitlements::Config,
+    session_config: &sessions::Config,
+    presences: &[presence::Player],
+) -> Result<()> {
+    let match_data = client
+        .source
+        .get()
+        .match_info(
+            client,
+            entitlements_config,
+            session_config,
+            &lobby.match_id,
+            presences,
+        )
+        .await?;
+
+    lobby.update_with_match_data(match_data, &client.content.get().agents);
+
+    Ok(())
+}
+
+/// The match as shown to the user.
+///
+/// `X` is any extra data the caller attaches to each player. It's flattened into the player
+/// when serialized.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ShortMatchData<X = ()> {
+    #[serde(rename = "matchId")]
+    pub match_id: String,
+    pub ingame: bool,
+    pub map: String,
+    pub mode: String,
+    pub layout: Layout,
+    #[serde(rename = "teamBased")]
+    pub team_based: bool,
+    pub players: Vec<ShortPlayer<X>>,
+    /// coaches and observers
+    pub spectators: Vec<ShortPlayer<X>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ShortPlayer<X = ()> {
+    pub uuid: String,
+    pub name: String,
+    pub team: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub character: Option<Agent>,
+    /// false while the agent is only hovered
+    pub locked: bool,
+    pub title: String,
+    #[serde(rename = "accountLevel", skip_serializing_if = "Option::is_none")]
+    pub account_level: Option<u32>,
+    #[serde(rename = "rankHistory")]
+    pub rank_history: Vec<CompetitiveTier>,
+    #[serde(rename = "partyId")]
+    pub party_id: String,
+    pub role: Role,
+    #[serde(rename = "loadState")]
+    pub load_state: LoadState,
+    pub status: PlayerStatus,
+    #[serde(flatten)]
+    pub extra: X,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LoadState {
+    /// names and ranks are still being fetched
+    Loading,
+    Loaded,
+}
+
+/// Part of a player's data that just finished loading. Fields that are still loading are omitted.
+#[derive(Clone, Debug, Serialize)]
+pub struct PlayerUpdate {
+    /// the match the player is in, so updates from a match that's since been left can be dropped
+    #[serde(rename = "matchId")]
+    pub match_id: String,
+    pub uuid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(rename = "nameStatus", skip_serializing_if = "Option::is_none")]
+    pub name_status: Option<FieldStatus>,
+    #[serde(rename = "rankHistory", skip_serializing_if = "Option::is_none")]
+    pub rank_history: Option<Vec<CompetitiveTier>>,
+    #[serde(rename = "rankStatus", skip_serializing_if = "Option::is_none")]
+    pub rank_status: Option<FieldStatus>,
+    #[serde(rename = "loadState")]
+    pub load_state: LoadState,
+}
+
+/// Maps each act in the history to its rank.
+pub fn rank_history(history: &History, tiers: &[CompetitiveTier]) -> Vec<CompetitiveTier> {
+    history
+        .iter()
+        .map(|a| CompetitiveTier {
+            act: a.season_id.clone(),
+            ..CompetitiveTier::from_act_tier(tiers, &a.episode_id, a.competitive_tier)
+        })
+        .collect()
+}
+
+impl<X: Default> ShortPlayer<X> {
+    fn from_player(
+        value: &Player,
+        agents: &[Agent],
+        tiers: &[CompetitiveTier],
+        load_state: LoadState,
+    ) -> Self {
+        trace!("{:#?}", value);
+        ShortPlayer {
+            uuid: value.puuid.clone(),
+            name: value.get_name(agents),
+            team: value.team.to_string(),
+            character: value.get_agent(agents),
+            locked: value.is_locked(),
+            title: value.title.clone(),
+            account_level: value.get_account_level(),
+            rank_history: rank_history(&value.competitive_history, tiers),
+            party_id: value.party_id.clone(),
+            role: value.role,
+            load_state,
+            status: value.status.clone(),
+            extra: X::default(),
+        }
+    }
+}
+
+impl<X> ShortPlayer<X> {
+    /// The player's rank in the given act. Acts they didn't play are left out of their history,
+    /// so the first entry isn't necessarily the current act.
+    pub fn rank_in(&self, act: &str) -> Option<&CompetitiveTier> {
+        self.rank_history.iter().find(|rank| rank.act == act)
+    }
+}
+
+impl<X: Default> ShortMatchData<X> {
+    pub fn from_match_data(
+        value: &MatchData,
+        agents: &[Agent],
+        tiers: &[CompetitiveTier],
+        load_state: LoadState,
+    ) -> Self {
+        ShortMatchData {
+            match_id: value.match_id.clone(),
+            ingame: value.ingame,
+            map: value.map.clone(),
+            mode: value.mode.clone(),
+            layout: value.layout,
+            team_based: value.layout.is_team_based(),
+            players: value
+                .players
+                .iter()
+                .map(|p| ShortPlayer::from_player(p, agents, tiers, load_state))
+                .collect(),
+            spectators: value
+                .spectators
+                .iter()
+                .map(|p| ShortPlayer::from_player(p, agents, tiers, load_state))
+                .collect(),
+        }
+    }
+}
+
+impl<X> ShortMatchData<X> {
+    /// Every player in the match, coaches and observers included.
+    pub fn participants(&self) -> impl Iterator<Item = &ShortPlayer<X>> {
+        self.players.iter().chain(&self.spectators)
+    }
+
+    pub fn participants_mut(&mut self) -> impl Iterator<Item = &mut ShortPlayer<X>> {
+        self.players.iter_mut().chain(self.spectators.iter_mut())
+    }
+
+    /// Numbers each party with more than one player, from 1 in the order they're listed, keyed
+    /// by party ID. Party IDs are meaningless to read, and players queueing alone don't need one.
+    /// Players without a presence have no party ID, so they're taken to be alone.
+    pub fn parties(&self) -> HashMap<String, usize> {
+        let mut sizes = HashMap::<&str, usize>::new();
+        for player in self.participants().filter(|p| !p.party_id.is_empty()) {
+            *sizes.entry(&player.party_id).or_default() += 1;
+        }
+
+        let mut parties = HashMap::new();
+        for player in self.participants().filter(|p| !p.party_id.is_empty()) {
+            if sizes[player.party_id.as_str()] > 1 && !parties.contains_key(&player.party_id) {
+                parties.insert(player.party_id.clone(), parties.len() + 1);
+            }
+        }
+
+        parties
+    }
+
+    /// Refreshes agents, teams and parties from a refetch of the same match.
+    /// Names and ranks are left alone, since they can't change mid-match.
+    pub fn update_with_match_data(&mut self, value: MatchData, agents: &[Agent]) {
+        self.ingame = value.ingame;
+        self.layout = value.layout;
+        self.team_based = value.layout.is_team_based();
+
+        for player in &mut self.players {
+            let updated_player = value.players.iter().find(|p| p.puuid == player.uuid);
+            let Some(updated_player) = updated_player else {
+                continue;
+            };
+
+            player.character = updated_player.get_agent(agents);
+            player.locked = updated_player.is_locked();
+            player.team = updated_player.team.to_string();
+            player.party_id = updated_player.party_id.clone();
+
+            // incognito players' names follow the hovered agent, so they need to be refreshed too
+            if updated_player.is_incognito() {
+                player.name = updated_player.get_name(agents);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(uuid: &str, party_id: &str) -> ShortPlayer {
+        ShortPlayer {
+            uuid: uuid.to_string(),
+            name: String::new(),
+            team: "blue".to_string(),
+            character: None,
+            locked: false,
+            title: String::new(),
+            account_level: None,
+            rank_history: Vec::new(),
+            party_id: party_id.to_string(),
+            role: Role::Player,
+            load_state: LoadState::Loaded,
+            status: PlayerStatus::default(),
+            extra: (),
+        }
+    }
+
+    #[test]
+    fn players_without_a_party_id_are_alone() {
+        let lobby = ShortMatchData {
+            match_id: "match".to_string(),
+            ingame: true,
+            map: String::new(),
+            mode: String::new(),
+            layout: Layout::Teams,
+            team_based: true,
+            players: vec![
+                player("a", ""),
+                player("b", "duo"),
+                player("c", ""),
+                player("d", "solo"),
+                player("e", "duo"),
+                player("f", ""),
+            ],
+            spectators: vec![player("g", "")],
+        };
+
+        assert_eq!(lobby.parties(), HashMap::from([("duo".to_string(), 1)]));
+    }
+}
@@ -1,6 +1,6 @@
 use color_eyre::eyre::Result;
 
-use crate::api::lockfile;
+use crate::lockfile;
 
 // the names returned by the api are confusing to say the least lol
 #[derive(Clone, Debug, serde::Deserialize)]
@@ -12,16 +12,15 @@ pub struct Config {
 }
 
 pub async fn login(lockfile: &lockfile::Config, http: &reqwest::Client) -> Result<Config> {
-    let entitlements_endpoint =
-        format!("https://127.0.0.1:{}/entitlements/v1/token", lockfile.port);
+    let entitlements_endpoint = format!("{}/entitlements/v1/token", lockfile.url());
 
-    let res = http
-        .get(&entitlements_endpoint)
-        .basic_auth("riot", Some(&lockfile.password))
-        .send()
-        .await?
-        .json::<Config>()
-        .await?;
+    let res = crate::traffic::send(
+        http.get(&entitlements_endpoint)
+            .basic_auth("riot", Some(&lockfile.password)),
+    )
+    .await?
+    .json::<Config>()
+    .await?;
 
     Ok(res)
 }
@@ -0,0 +1,69 @@
+use color_eyre::eyre::Result;
+use serde::Deserialize;
+
+use super::presence::{self, Availability, Presences};
+use crate::lockfile;
+
+#[derive(Debug, Deserialize)]
+struct FriendsResponse {
+    friends: Vec<RawFriend>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawFriend {
+    puuid: String,
+    #[serde(default)]
+    game_name: String,
+    #[serde(default)]
+    game_tag: String,
+}
+
+/// Someone on the user's friends list.
+#[derive(Clone, Debug)]
+pub struct Friend {
+    pub puuid: String,
+    pub game_name: String,
+    pub game_tag: String,
+    /// Offline if the chat service has no presence for them
+    pub availability: Availability,
+    /// their Valorant presence. None unless they're playing
+    pub presence: Option<presence::Player>,
+}
+
+/// * `presences` - Everyone's presences, from [`presence::get_all_presences`].
+pub async fn get_friends(
+    lockfile: &lockfile::Config,
+    http: &reqwest::Client,
+    mut presences: Presences,
+) -> Result<Vec<Friend>> {
+    let friends_endpoint = format!("{}/chat/v4/friends", lockfile.url());
+
+    let friends = crate::traffic::send(
+        http.get(friends_endpoint)
+            .basic_auth("riot", Some(&lockfile.password)),
+    )
+    .await?
+    .json::<FriendsResponse>()
+    .await?
+    .friends;
+
+    // friends playing other games are still online, so every presence counts here
+    Ok(friends
+        .into_iter()
+        .map(|friend| Friend {
+            availability: presences
+                .availability
+                .get(&friend.puuid)
+                .copied()
+                .unwrap_or(Availability::Offline),
+            presence: presences
+                .players
+                .iter()
+                .position(|p| p.puuid == friend.puuid)
+                .map(|index| presences.players.swap_remove(index)),
+            puuid: friend.puuid,
+            game_name: friend.game_name,
+            game_tag: friend.game_tag,
+        })
+        .collect())
+}
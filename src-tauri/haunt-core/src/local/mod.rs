@@ -1,3 +1,4 @@
 pub mod entitlements;
+pub mod friends;
 pub mod presence;
 pub mod sessions;
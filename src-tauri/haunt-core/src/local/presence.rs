@@ -0,0 +1,303 @@
+#![allow(unused)]
+use std::{collections::HashMap, path::Path};
+
+use base64::prelude::*;
+use color_eyre::eyre::{bail, Report, Result};
+use heck::ToTitleCase;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::lockfile;
+
+const DM_UUID: &str = "a8790ec5-4237-f2f0-e93b-08a8e89865b2";
+const SPIKE_RUSH_UUID: &str = "e921d1e6-416b-c31f-1291-74930c330b7b";
+const GGTEAM_UUID: &str = "a4ed6518-4741-6dcb-35bd-f884aecdc859";
+const ONEFA_UUID: &str = "96bd3920-4f36-d026-2b28-c683eb0bcac5";
+const SNOWBALL_UUID: &str = "57038d6d-49b1-3a74-c5ef-3395d9f23a97";
+
+/// Newer clients group the private presence under these, with the same keys older clients send
+/// at the top level.
+const NESTED_PRESENCE_KEYS: [&str; 3] = [
+    "matchPresenceData",
+    "partyPresenceData",
+    "playerPresenceData",
+];
+
+#[derive(Debug, Deserialize)]
+struct PresenceResponse {
+    // each presence is decoded on its own, so one odd presence doesn't lose the rest
+    presences: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum Product {
+    Valorant,
+    LeagueOfLegends,
+    /// anything else the Riot Client runs, e.g. Legends of Runeterra
+    #[serde(other)]
+    Other,
+}
+
+/// What a player's chat status is set to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Availability {
+    Away,
+    /// "dnd", which the client sets during matches
+    #[serde(alias = "dnd")]
+    Busy,
+    Mobile,
+    Offline,
+    /// "chat" to the chat service, or anything it adds later
+    #[default]
+    #[serde(other)]
+    Online,
+}
+
+#[derive(Debug, Deserialize)]
+struct Presence {
+    puuid: String,
+    #[serde(default)]
+    game_name: String,
+    #[serde(default)]
+    game_tag: String,
+    product: Product,
+    #[serde(default, rename = "state")]
+    availability: Availability,
+    private: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Player {
+    pub puuid: String,
+    pub game_name: String,
+    pub game_tag: String,
+    #[serde(default)]
+    pub availability: Availability,
+    pub private: Private,
+}
+
+/// Anything missing is left empty, since the fields differ between client versions.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Private {
+    pub is_valid: bool,
+    pub match_map: String,
+    pub party_id: String,
+    pub player_card_id: String,
+    pub player_title_id: String,
+    pub preferred_level_border_id: String,
+    pub account_level: u32,
+    pub competitive_tier: u32,
+    pub queue_id: String, // gamemode id!
+    /// MENUS, PREGAME or INGAME
+    pub session_loop_state: String,
+    /// MATCHMAKING while the party is in queue
+    pub party_state: String,
+    pub party_size: u32,
+    /// rounds won by the party owner's team, while they're ingame
+    pub party_owner_match_score_ally_team: u32,
+    pub party_owner_match_score_enemy_team: u32,
+}
+
+impl Private {
+    /// Decodes a private presence in either the flat or the nested schema.
+    fn decode(private: &str) -> Result<Self> {
+        // private data is base64 encoded json
+        let decoded = BASE64_STANDARD.decode(private)?;
+        let Value::Object(mut fields) = serde_json::from_slice::<Value>(&decoded)? else {
+            bail!("Private presence isn't a JSON object.");
+        };
+
+        for key in NESTED_PRESENCE_KEYS {
+            if let Some(Value::Object(nested)) = fields.remove(key) {
+                fields.extend(nested);
+            }
+        }
+
+        Ok(serde_json::from_value(Value::Object(fields))?)
+    }
+}
+
+// converts the raw presence response to a Player object with a puuid and decoded Private presence
+impl TryFrom<Presence> for Player {
+    type Error = Report;
+
+    fn try_from(value: Presence) -> Result<Self> {
+        let Some(private) = &value.private else {
+            bail!("{} has no private presence.", value.puuid);
+        };
+        let private = Private::decode(private)?;
+
+        Ok(Player {
+            puuid: value.puuid,
+            game_name: value.game_name,
+            game_tag: value.game_tag,
+            availability: value.availability,
+            private,
+        })
+    }
+}
+
+/// Every presence the chat service knows about, whatever the game.
+async fn fetch_presences(
+    lockfile: &lockfile::Config,
+    http: &reqwest::Client,
+) -> Result<Vec<Presence>> {
+    let presences_endpoint = format!("{}/chat/v4/presences", lockfile.url());
+
+    let presences = crate::traffic::send(
+        http.get(presences_endpoint)
+            .basic_auth("riot", Some(&lockfile.password)),
+    )
+    .await?
+    .json::<PresenceResponse>()
+    .await?
+    .presences;
+
+    // anything that can't be decoded is skipped, a newer client shouldn't take the rest down
+    Ok(presences
+        .into_iter()
+        .filter_map(|p| match serde_json::from_value::<Presence>(p) {
+            Ok(p) => Some(p),
+            Err(why) => {
+                warn!("Skipping unreadable presence: {why}");
+                None
+            }
+        })
+        .collect())
+}
+
+/// Everyone the chat service has a presence for, from a single request.
+#[derive(Debug, Default)]
+pub struct Presences {
+    /// chat status of everyone, whatever they're playing
+    pub availability: HashMap<String, Availability>,
+    /// everyone playing Valorant, as [`get_presences`] returns them
+    pub players: Vec<Player>,
+}
+
+pub async fn get_presences(
+    lockfile: &lockfile::Config,
+    http: &reqwest::Client,
+) -> Result<Vec<Player>> {
+    Ok(get_all_presences(lockfile, http).await?.players)
+}
+
+/// Every presence, whatever the game. The friends list needs both the chat statuses and the
+/// Valorant presences, which all come from the same request.
+pub async fn get_all_presences(
+    lockfile: &lockfile::Config,
+    http: &reqwest::Client,
+) -> Result<Presences> {
+    let presences = fetch_presences(lockfile, http).await?;
+    let availability = presences
+        .iter()
+        .map(|p| (p.puuid.clone(), p.availability))
+        .collect();
+
+    // we'll only collect players playing val with private statuses
+    // into_iter consumes the original value and owns its values rather than providing references
+    // we also augment presences to Players to make it easier to work with later
+    let players = presences
+        .into_iter()
+        .filter(|p| p.product == Product::Valorant && p.private.is_some())
+        .filter_map(|p| {
+            debug!("Found presence: {:#?}", p);
+            let puuid = p.puuid.clone();
+            match Player::try_from(p) {
+                Ok(player) => Some(player),
+                Err(why) => {
+                    warn!("Skipping presence for {puuid} that couldn't be decoded: {why}");
+                    None
+                }
+            }
+        })
+        .collect();
+
+    Ok(Presences {
+        availability,
+        players,
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MatchInfo {
+    gamemode: String,
+    map: Map,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub enum Map {
+    Bind,
+    Haven,
+    Split,
+    Ascent,
+    Icebox,
+    Breeze,
+    Fracture,
+    TheRange,
+    Unknown,
+}
+
+impl From<&Player> for MatchInfo {
+    fn from(player: &Player) -> Self {
+        let map = Map::from(player.private.match_map.clone());
+
+        let gamemode_name = match map {
+            Map::TheRange => String::from("The Range"),
+            _ => queue_name(&player.private.queue_id),
+        };
+
+        Self {
+            gamemode: gamemode_name,
+            map: Map::from(player.private.match_map.clone()),
+        }
+    }
+}
+
+/// Readable name for a queue id from a presence.
+pub fn queue_name(queue_id: &str) -> String {
+    match queue_id {
+        "competitive" => String::from("Competitive"),
+        "unrated" => String::from("Unrated"),
+        "newmap" => String::from("New Map"),
+        // "deathmatch" => get_map_name(DM_UUID),
+        // "spikerush" => get_map_name(SPIKE_RUSH_UUID),
+        // "ggteam" => get_map_name(GGTEAM_UUID),
+        // "onefa" => get_map_name(ONEFA_UUID),
+        // "snowball" => get_map_name(SNOWBALL_UUID),
+        m => m.to_title_case(),
+    }
+}
+
+impl From<String> for Map {
+    fn from(value: String) -> Self {
+        let value = value.split('/').next_back().unwrap();
+        match value {
+            "Bind" => Self::Bind,
+            "Haven" => Self::Haven,
+            "Split" => Self::Split,
+            "Ascent" => Self::Ascent,
+            "Icebox" => Self::Icebox,
+            "Breeze" => Self::Breeze,
+            "Fracture" => Self::Fracture,
+            "Range" => Self::TheRange,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+fn get_map_name(uuid: &'static str) -> String {
+    let maps_path = Path::new(&std::env::var("LOCALAPPDATA").expect("No LocalAppData var!"))
+        .join("Haunt")
+        .join("ValAPI")
+        .join("maps.txt");
+
+    String::from("")
+}
+
+pub async fn get_match_info(puuid: &str, players: &[Player]) -> MatchInfo {
+    MatchInfo::from(players.iter().find(|p| p.puuid == puuid).unwrap())
+}
@@ -1,9 +1,9 @@
 use std::collections::HashMap;
 
 use color_eyre::eyre::{eyre, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::api::lockfile;
+use crate::lockfile;
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -33,7 +33,8 @@ pub struct Config {
     pub version: String,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Region {
     Na,
     Latam,
@@ -56,20 +57,21 @@ impl From<&String> for Region {
     }
 }
 
-impl std::string::ToString for Region {
-    fn to_string(&self) -> String {
-        match self {
-            Region::Na => "na".to_string(),
-            Region::Latam => "latam".to_string(),
-            Region::Br => "br".to_string(),
-            Region::Eu => "eu".to_string(),
-            Region::Ap => "ap".to_string(),
-            Region::Kr => "kr".to_string(),
-        }
+impl std::fmt::Display for Region {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Region::Na => "na",
+            Region::Latam => "latam",
+            Region::Br => "br",
+            Region::Eu => "eu",
+            Region::Ap => "ap",
+            Region::Kr => "kr",
+        })
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Shard {
     Na,
     Pbe,
@@ -90,19 +92,19 @@ impl From<&String> for Shard {
     }
 }
 
-impl std::string::ToString for Shard {
-    fn to_string(&self) -> String {
-        match self {
-            Shard::Na => "na".to_string(),
-            Shard::Pbe => "pbe".to_string(),
-            Shard::Eu => "eu".to_string(),
-            Shard::Ap => "ap".to_string(),
-            Shard::Kr => "kr".to_string(),
-        }
+impl std::fmt::Display for Shard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Shard::Na => "na",
+            Shard::Pbe => "pbe",
+            Shard::Eu => "eu",
+            Shard::Ap => "ap",
+            Shard::Kr => "kr",
+        })
     }
 }
 
-fn get_arg(arguments: &Vec<String>, prefix: &str) -> String {
+fn get_arg(arguments: &[String], prefix: &str) -> String {
     arguments
         .iter()
         .find(|&arg| arg.starts_with(prefix))
@@ -120,12 +122,12 @@ impl From<&SessionsResponse> for Config {
         let deployment = get_arg(&arguments, "-ares-deployment=");
         let puuid = get_arg(&arguments, "-subject=");
 
-        return Self {
+        Self {
             puuid: puuid.to_string(),
             shard: Shard::from(&deployment),
             region: Region::from(&deployment),
             version: "".to_string(),
-        };
+        }
     }
 }
 
@@ -149,19 +151,20 @@ pub async fn load_config(
     offline_http: &reqwest::Client,
     http: &reqwest::Client,
 ) -> Result<Config> {
-    let sessions_response = offline_http
-        .get(format!(
-            "https://127.0.0.1:{}/product-session/v1/external-sessions",
-            lockfile.port
-        ))
-        .basic_auth("riot", Some(&lockfile.password)) // this b64 encodes for us omg!
-        .send()
-        .await?
-        .json::<HashMap<String, SessionsResponse>>()
-        .await?;
+    let sessions_response = crate::traffic::send(
+        offline_http
+            .get(format!(
+                "{}/product-session/v1/external-sessions",
+                lockfile.url()
+            ))
+            .basic_auth("riot", Some(&lockfile.password)), // this b64 encodes for us omg!
+    )
+    .await?
+    .json::<HashMap<String, SessionsResponse>>()
+    .await?;
 
     let version = http
-        .get("https://valorant-api.com/v1/version")
+        .get(format!("{}/version", crate::valapi::base_url()))
         .send()
         .await?
         .json::<VersionResponse>()
@@ -177,5 +180,5 @@ pub async fn load_config(
     let mut valorant_config = Config::from(valorant_config);
     valorant_config.version = version.data.riot_client_version;
 
-    return Ok(Config::from(valorant_config));
+    Ok(valorant_config)
 }
@@ -9,6 +9,13 @@ pub struct Config {
     pub protocol: String,
 }
 
+impl Config {
+    /// Base URL of the local client API. The real client always uses https.
+    pub fn url(&self) -> String {
+        format!("{}://127.0.0.1:{}", self.protocol, self.port)
+    }
+}
+
 #[derive(serde::Serialize)]
 pub struct ParseConfigError;
 
@@ -21,17 +28,28 @@ impl FromStr for Config {
             return Err(ParseConfigError);
         }
 
-        return Ok(Self {
+        Ok(Self {
             name: parts[0].to_string(),
-            pid: parts[1].parse().or_else(|_| Err(ParseConfigError))?,
-            port: parts[2].parse().or_else(|_| Err(ParseConfigError))?,
+            pid: parts[1].parse().map_err(|_| ParseConfigError)?,
+            port: parts[2].parse().map_err(|_| ParseConfigError)?,
             password: parts[3].to_string(),
             protocol: parts[4].to_string(),
-        });
+        })
     }
 }
 
 pub fn load_config() -> Option<Config> {
+    // replayed requests never reach the client, so nothing needs to be running
+    if crate::traffic::is_replaying() {
+        return Some(Config {
+            name: "Riot Client".to_string(),
+            pid: 0,
+            port: 0,
+            password: "".to_string(),
+            protocol: "https".to_string(),
+        });
+    }
+
     let lockfile_path = Path::new(&std::env::var("LOCALAPPDATA").expect("No LocalAppData var!"))
         .join("Riot Games")
         .join("Riot Client")
@@ -47,5 +65,5 @@ pub fn load_config() -> Option<Config> {
 
     let loaded_config = lockfile.parse::<Config>().ok()?;
 
-    return Some(loaded_config.clone());
+    Some(loaded_config)
 }
@@ -0,0 +1,101 @@
+use crate::{
+    local::{entitlements, presence, sessions},
+    lockfile, Client,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum LoginFail {
+    #[error("Unable to load lockfile config. Valorant probably isn't running.")]
+    Lockfile,
+    #[error("Unable to load entitlements config. Valorant probably isn't running.")]
+    Entitlements,
+    #[error("Unable to load session.")]
+    Session,
+}
+
+impl serde::Serialize for LoginFail {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_u8(match self {
+            LoginFail::Lockfile => 0,
+            LoginFail::Entitlements => 1,
+            LoginFail::Session => 2,
+        })
+    }
+}
+
+/// Everything needed to make requests as the logged in user.
+#[derive(Clone, Debug)]
+pub struct Login {
+    pub lockfile: lockfile::Config,
+    pub entitlements: entitlements::Config,
+    pub session: sessions::Config,
+    /// the user's own presence, as of login
+    pub user: presence::Player,
+}
+
+/// Logs in through the running Riot client. Err signifies the stage login failed at.
+pub async fn login(client: &Client) -> Result<Login, LoginFail> {
+    info!("Loading lockfile...");
+
+    let Some(lockfile_config) = lockfile::load_config() else {
+        error!("Unable to load lockfile config. Valorant probably isn't running.");
+        return Err(LoginFail::Lockfile);
+    };
+    info!("Lockfile config loaded successfully.");
+    debug!("{:#?}", lockfile_config);
+
+    info!("Loading entitlements...");
+
+    let entitlements_config = match entitlements::login(&lockfile_config, &client.offline_http)
+        .await
+    {
+        Ok(config) => config,
+        Err(e) => {
+            error!("Unable to load entitlements config: {e}");
+            warn!("This is probably not an issue with Haunt! Valorant is probably not running.");
+            return Err(LoginFail::Entitlements);
+        }
+    };
+    info!("Entitlements config loaded successfully.");
+    debug!("{:#?}", entitlements_config);
+
+    info!("Loading session...");
+
+    let mut session_config =
+        match sessions::load_config(&lockfile_config, &client.offline_http, &client.http).await {
+            Ok(config) => config,
+            Err(why) => {
+                error!("Unable to load session config: {why}");
+                warn!("It looks like Valorant isn't logged in.");
+                return Err(LoginFail::Session);
+            }
+        };
+    client.settings.get().apply_overrides(&mut session_config);
+    info!("Session config loaded successfully.");
+    debug!("{:#?}", session_config);
+
+    info!("Getting player's username...");
+    let Ok(presences) = presence::get_presences(&lockfile_config, &client.offline_http).await
+    else {
+        return Err(LoginFail::Session);
+    };
+
+    let Some(user) = presences
+        .into_iter()
+        .find(|presence| presence.puuid == session_config.puuid)
+    else {
+        error!("Unable to find user in presences. User is probably not logged in.");
+        return Err(LoginFail::Session);
+    };
+
+    info!("Playing as {}", user.game_name);
+    Ok(Login {
+        lockfile: lockfile_config,
+        entitlements: entitlements_config,
+        session: session_config,
+        user,
+    })
+}
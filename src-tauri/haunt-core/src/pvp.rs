@@ -0,0 +1,105 @@
+use super::local::{entitlements, sessions};
+use ratelimit::RateLimiter;
+
+pub mod cache;
+pub mod matchdata;
+pub mod matchdetails;
+pub mod mmr;
+pub mod names;
+pub mod party;
+pub mod ratelimit;
+
+/// base64 encoded client platform json. some pd/glz endpoints refuse requests without it
+const CLIENT_PLATFORM: &str = "ew0KCSJwbGF0Zm9ybVR5cGUiOiAiUEMiLA0KCSJwbGF0Zm9ybU9TIjogIldpbmRvd3MiLA0KCSJwbGF0Zm9ybU9TVmVyc2lvbiI6ICIxMC4wLjE5MDQyLjEuMjU2LjY0Yml0IiwNCgkicGxhdGZvcm1DaGlwc2V0IjogIlVua25vd24iDQp9";
+
+/// A base URL set through the environment, for pointing Haunt at something other than Riot.
+fn url_override(var: &str) -> Option<String> {
+    match std::env::var(var) {
+        Ok(url) if !url.is_empty() => Some(url.trim_end_matches('/').to_string()),
+        _ => None,
+    }
+}
+
+/// Base URL for glz requests. Set `HAUNT_GLZ_URL` to point Haunt elsewhere, e.g. at haunt-sim.
+pub fn glz_url(session: &sessions::Config) -> String {
+    url_override("HAUNT_GLZ_URL").unwrap_or_else(|| {
+        format!(
+            "https://glz-{}-1.{}.a.pvp.net",
+            session.region, session.shard
+        )
+    })
+}
+
+/// Base URL for pd requests. Set `HAUNT_PD_URL` to point Haunt elsewhere, e.g. at haunt-sim.
+pub fn pd_url(session: &sessions::Config) -> String {
+    url_override("HAUNT_PD_URL")
+        .unwrap_or_else(|| format!("https://pd.{}.a.pvp.net", session.shard))
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CurrentGameResponse {
+    #[serde(rename = "MatchID")]
+    match_id: Option<String>,
+}
+
+pub async fn find_match_id(
+    entitlements: &entitlements::Config,
+    http: &reqwest::Client,
+    limiter: &RateLimiter,
+    session: &sessions::Config,
+) -> Option<String> {
+    debug!("Checking ingame api for player...");
+
+    let ingame_endpoint = format!(
+        "{}/core-game/v1/players/{}",
+        glz_url(session),
+        &session.puuid
+    );
+
+    let res = limiter
+        .send(
+            http.get(&ingame_endpoint)
+                .bearer_auth(&entitlements.token)
+                .header("X-Riot-Entitlements-JWT", &entitlements.jwt),
+        )
+        .await;
+
+    // Rust doesn't support if-let chaining with a bound variable in the second condition
+    // a match with a guard is basically the easiest way achieve the same effect
+    // we'll return early if we detect the player either in game or in a pre-game lobby
+    match res {
+        Ok(res) if res.status().is_success() => {
+            debug!("Found player in game.");
+            let match_id = res.json::<CurrentGameResponse>().await.unwrap().match_id;
+
+            return match_id;
+        }
+        _ => (),
+    }
+
+    debug!("Player not found. Falling back to pregame...");
+
+    let pregame_endpoint = format!("{}/pregame/v1/players/{}", glz_url(session), &session.puuid);
+
+    let res = limiter
+        .send(
+            http.get(&pregame_endpoint)
+                .bearer_auth(&entitlements.token)
+                .header("X-Riot-Entitlements-JWT", &entitlements.jwt),
+        )
+        .await;
+
+    // there's nowhere else to check after this so we'll just assume false if they're not here
+    match res {
+        Ok(res) if res.status().is_success() => {
+            debug!("Player found in pregame lobby.");
+            let match_id = res.json::<CurrentGameResponse>().await.unwrap().match_id;
+            match_id
+        }
+        _ => {
+            debug!("Player not found in a match.");
+            None
+        }
+    }
+}
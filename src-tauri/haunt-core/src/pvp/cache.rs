@@ -0,0 +1,183 @@
+use std::{collections::HashMap, fs, path::PathBuf, sync::Mutex, time::Duration};
+
+use color_eyre::eyre::Result;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// The pd/glz lookups worth caching. Live match state (core-game, pregame, parties) changes
+/// too often and is never cached.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Endpoint {
+    /// Riot IDs from the name service
+    Names,
+    /// competitive history from the mmr endpoint
+    Mmr,
+}
+
+impl Endpoint {
+    /// How long a response stays fresh.
+    fn ttl(self) -> Duration {
+        match self {
+            // riot ids can change, but rarely
+            Endpoint::Names => Duration::from_secs(24 * 60 * 60),
+            // ranks only change once a match is over
+            Endpoint::Mmr => Duration::from_secs(30 * 60),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct Entry {
+    /// unix timestamp, in seconds
+    stored_at: i64,
+    value: serde_json::Value,
+}
+
+impl Entry {
+    fn is_fresh(&self, endpoint: Endpoint) -> bool {
+        let age = chrono::Utc::now().timestamp() - self.stored_at;
+        age >= 0 && (age as u64) < endpoint.ttl().as_secs()
+    }
+}
+
+type Entries = HashMap<Endpoint, HashMap<String, Entry>>;
+
+/// Drops every expired entry, returning whether any were.
+fn prune(entries: &mut Entries) -> bool {
+    let mut pruned = false;
+    for (endpoint, entries) in entries.iter_mut() {
+        let before = entries.len();
+        entries.retain(|_, entry| entry.is_fresh(*endpoint));
+        pruned |= entries.len() != before;
+    }
+
+    pruned
+}
+
+#[derive(Debug, Default)]
+struct State {
+    entries: Entries,
+    /// whether anything changed since the cache was last written
+    dirty: bool,
+}
+
+/// Caches pd lookups per player, so the same people aren't looked up again every match.
+/// Changes are kept in memory until [`ResponseCache::flush`] writes them to disk.
+#[derive(Debug, Default)]
+pub struct ResponseCache {
+    // entries and the dirty flag share a lock, so they can't be taken in different orders
+    state: Mutex<State>,
+    path: Mutex<Option<PathBuf>>,
+}
+
+impl ResponseCache {
+    /// Loads entries saved by a previous run and persists to the same file from then on.
+    /// Expired entries are dropped.
+    pub fn load(&self, path: PathBuf) {
+        let loaded = fs::read_to_string(&path)
+            .map_err(color_eyre::Report::from)
+            .and_then(|file| Ok(serde_json::from_str::<Entries>(&file)?));
+
+        match loaded {
+            Ok(mut entries) => {
+                prune(&mut entries);
+
+                debug!(
+                    "Loaded {} cached responses.",
+                    entries.values().map(HashMap::len).sum::<usize>()
+                );
+                *self.state.lock().unwrap() = State {
+                    entries,
+                    dirty: false,
+                };
+            }
+            Err(why) => info!("No usable response cache at {}: {why}", path.display()),
+        }
+
+        *self.path.lock().unwrap() = Some(path);
+    }
+
+    /// Returns the cached response for `key` if it's still fresh. Stale responses are dropped.
+    pub fn get<T: DeserializeOwned>(&self, endpoint: Endpoint, key: &str) -> Option<T> {
+        let mut state = self.state.lock().unwrap();
+        let entries = state.entries.get_mut(&endpoint)?;
+        let entry = entries.get(key)?;
+
+        if !entry.is_fresh(endpoint) {
+            entries.remove(key);
+            state.dirty = true;
+            return None;
+        }
+
+        trace!("Cache hit for {:?} {key}", endpoint);
+        serde_json::from_value(entry.value.clone()).ok()
+    }
+
+    pub fn insert<T: Serialize>(&self, endpoint: Endpoint, key: &str, value: &T) {
+        let Ok(value) = serde_json::to_value(value) else {
+            return;
+        };
+
+        let entry = Entry {
+            stored_at: chrono::Utc::now().timestamp(),
+            value,
+        };
+
+        let mut state = self.state.lock().unwrap();
+        state
+            .entries
+            .entry(endpoint)
+            .or_default()
+            .insert(key.to_string(), entry);
+        state.dirty = true;
+    }
+
+    /// Drops cached responses.
+    ///
+    /// * `endpoint` - Only drop responses from this endpoint. Drops everything if None.
+    /// * `key` - Only drop responses for this key, e.g. a single player's PUUID.
+    pub fn invalidate(&self, endpoint: Option<Endpoint>, key: Option<&str>) {
+        let mut state = self.state.lock().unwrap();
+
+        for (_, entries) in state
+            .entries
+            .iter_mut()
+            .filter(|(e, _)| endpoint.is_none_or(|endpoint| **e == endpoint))
+        {
+            match key {
+                Some(key) => {
+                    entries.remove(key);
+                }
+                None => entries.clear(),
+            }
+        }
+
+        state.dirty = true;
+    }
+
+    /// Writes the cache to disk if anything changed since it was last written. Expired entries
+    /// are dropped first.
+    pub fn flush(&self) -> Result<()> {
+        let Some(path) = self.path.lock().unwrap().clone() else {
+            return Ok(());
+        };
+
+        let mut state = self.state.lock().unwrap();
+        if prune(&mut state.entries) {
+            state.dirty = true;
+        }
+        if !state.dirty {
+            return Ok(());
+        }
+
+        let file = serde_json::to_string(&state.entries)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, file)?;
+
+        state.dirty = false;
+
+        Ok(())
+    }
+}
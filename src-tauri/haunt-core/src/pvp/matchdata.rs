@@ -0,0 +1,365 @@
+use std::{collections::HashMap, fmt::Display};
+
+use color_eyre::Result;
+
+use crate::{
+    local::{entitlements, sessions},
+    pvp::ratelimit::RateLimiter,
+    valapi::agents::Agent,
+};
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+mod custom;
+mod ingame;
+mod pregame;
+
+#[derive(Debug)]
+pub struct MatchData {
+    /// for custom games this is the party ID, since there's no match yet
+    pub match_id: String,
+    pub ingame: bool,
+    pub map: String,
+    pub mode: String,
+    pub layout: Layout,
+    pub players: Vec<Player>,
+    /// coaches and observers. they're kept out of `players` so they don't end up on a team
+    pub spectators: Vec<Player>,
+}
+
+impl MatchData {
+    pub(crate) fn new(
+        ingame: bool,
+        map: String,
+        mode: String,
+        players: Vec<Player>,
+        spectators: Vec<Player>,
+    ) -> Self {
+        let mut match_data = MatchData {
+            match_id: String::new(),
+            ingame,
+            layout: Self::detect_layout(&mode, &players),
+            map,
+            mode,
+            players,
+            spectators,
+        };
+        match_data.number_incognito_players();
+
+        match_data
+    }
+
+    /// Every subject in the match, including coaches and observers.
+    pub fn participants(&self) -> impl Iterator<Item = &Player> {
+        self.players.iter().chain(self.spectators.iter())
+    }
+
+    /// Mutable version of [`MatchData::participants`].
+    pub fn participants_mut(&mut self) -> impl Iterator<Item = &mut Player> {
+        self.players.iter_mut().chain(self.spectators.iter_mut())
+    }
+
+    /// Party IDs only come from presences, so only friends and the user's party get one.
+    pub(crate) fn assign_parties(&mut self, presences: &[crate::local::presence::Player]) {
+        for player in self.participants_mut() {
+            let presence = presences.iter().find(|p| p.puuid == player.puuid);
+            let Some(presence) = presence else {
+                warn!("Player {} not found in presences", player.puuid);
+                continue;
+            };
+
+            player.party_id = presence.private.party_id.clone();
+        }
+    }
+
+    /// Works out how players are split up, from the mode and the team IDs they were given.
+    fn detect_layout(mode: &str, players: &[Player]) -> Layout {
+        if mode.contains("HURM") {
+            return Layout::TeamDeathmatch;
+        }
+
+        if mode.contains("Deathmatch") {
+            return Layout::FreeForAll;
+        }
+
+        // anything that isn't blue/red gets a team ID per player, so check for that as a fallback
+        match players.iter().any(|p| matches!(p.team, Team::Other(_))) {
+            true => Layout::FreeForAll,
+            false => Layout::Teams,
+        }
+    }
+
+    /// Incognito players are named after their agent, which isn't unique in FFA modes.
+    /// Numbers every incognito player that would otherwise share a name with someone else.
+    fn number_incognito_players(&mut self) {
+        let mut counts: HashMap<Option<String>, usize> = HashMap::new();
+        for player in self.players.iter().filter(|p| p.incognito) {
+            *counts.entry(player.character.uuid()).or_default() += 1;
+        }
+
+        let mut seen: HashMap<Option<String>, usize> = HashMap::new();
+        for player in self.players.iter_mut().filter(|p| p.incognito) {
+            let key = player.character.uuid();
+            if counts.get(&key).copied().unwrap_or_default() < 2 {
+                player.incognito_index = None;
+                continue;
+            }
+
+            let index = seen.entry(key).or_default();
+            *index += 1;
+            player.incognito_index = Some(*index);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Layout {
+    /// two teams, blue and red
+    Teams,
+    /// two teams, but respawns and no spike
+    TeamDeathmatch,
+    /// every player is on their own team
+    FreeForAll,
+}
+
+impl Layout {
+    pub fn is_team_based(&self) -> bool {
+        !matches!(self, Layout::FreeForAll)
+    }
+}
+
+#[derive(Debug)]
+pub struct Player {
+    pub puuid: String,
+    name: String,
+    pub team: Team,
+    character: Character,
+    pub card: String,
+    pub title: String,
+    account_level: u32,
+    pub border: String,
+    incognito: bool,
+    hide_account_level: bool,
+    pub competitive_history: super::mmr::History,
+    pub party_id: String,
+    pub role: Role,
+    /// set when more than one incognito player would end up with the same name
+    incognito_index: Option<usize>,
+    pub status: PlayerStatus,
+}
+
+/// Whether each piece of enrichment data made it onto the player.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerStatus {
+    pub name: FieldStatus,
+    pub rank_history: FieldStatus,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(tag = "status", content = "reason", rename_all = "camelCase")]
+pub enum FieldStatus {
+    /// hasn't been fetched yet
+    #[default]
+    Pending,
+    Loaded,
+    /// the pd service failed, so this came from the player's presence instead
+    Fallback,
+    /// fetched fine, but Riot had nothing for this player
+    Unavailable,
+    /// turned off in settings, so it was never fetched
+    Disabled,
+    Error(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Role {
+    Player,
+    Coach,
+    Observer,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Team {
+    Blue,
+    Red,
+    /// observers in custom games sit on the neutral team
+    Neutral,
+    /// deathmatch gives every player their own team, keyed by an ID we keep around
+    Other(String),
+}
+
+// serde can't fall back to a newtype variant with the original string, so this is done by hand
+impl<'de> Deserialize<'de> for Team {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let id = String::deserialize(deserializer)?;
+
+        Ok(match id.as_str() {
+            "Blue" => Team::Blue,
+            "Red" => Team::Red,
+            "Neutral" => Team::Neutral,
+            _ => Team::Other(id),
+        })
+    }
+}
+
+impl Display for Team {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Team::Blue => "blue",
+            Team::Red => "red",
+            Team::Neutral => "neutral",
+            Team::Other(id) => id,
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub enum Character {
+    None,
+    Hovered(String),
+    Locked(String),
+}
+
+impl Character {
+    /// The agent UUID, if one has been picked.
+    fn uuid(&self) -> Option<String> {
+        match self {
+            Character::None => None,
+            Character::Hovered(c) | Character::Locked(c) => Some(c.clone()),
+        }
+    }
+}
+
+impl Player {
+    /// A player as the match endpoints describe them, before names and ranks are filled in.
+    pub(crate) fn new(
+        puuid: String,
+        team: Team,
+        character: Character,
+        account_level: u32,
+        incognito: bool,
+        hide_account_level: bool,
+    ) -> Self {
+        Player {
+            puuid,
+            name: String::new(),
+            team,
+            character,
+            card: String::new(),
+            title: String::new(),
+            account_level,
+            border: String::new(),
+            incognito,
+            hide_account_level,
+            competitive_history: Vec::new(),
+            party_id: String::new(),
+            role: Role::Player,
+            incognito_index: None,
+            status: PlayerStatus::default(),
+        }
+    }
+
+    /// Used to fill names from the Valorant Name API.
+    ///
+    /// * `name` - The name to fill.
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    /// Returns the player's name, or an anonymized version if incognito.
+    pub fn get_name(&self, agents: &[Agent]) -> String {
+        match self.incognito {
+            true => self.get_incognito_name(agents),
+            false => self.name.clone(),
+        }
+    }
+
+    fn get_incognito_name(&self, agents: &[Agent]) -> String {
+        let name = match &self.character {
+            Character::None => "Player".to_string(),
+            Character::Hovered(ref c) | Character::Locked(ref c) => {
+                match agent_from_uuid(agents, c) {
+                    Some(agent) => agent.display_name,
+                    None => "Player".to_string(),
+                }
+            }
+        };
+
+        match self.incognito_index {
+            Some(index) => format!("{name} {index}"),
+            None => name,
+        }
+    }
+
+    /// Whether the player has locked in their agent, rather than just hovering it.
+    pub fn is_locked(&self) -> bool {
+        matches!(self.character, Character::Locked(_))
+    }
+
+    /// Whether the player's name is hidden.
+    pub fn is_incognito(&self) -> bool {
+        self.incognito
+    }
+
+    /// Returns the player's account level, or None if hidden.
+    pub fn get_account_level(&self) -> Option<u32> {
+        match self.hide_account_level {
+            true => None,
+            false => Some(self.account_level),
+        }
+    }
+
+    /// Returns the player's agent, or None if not selected yet or agent unknown.
+    ///
+    /// * `agents` - The list of agents fetched from ValAPI.
+    pub fn get_agent(&self, agents: &[Agent]) -> Option<Agent> {
+        trace!("Getting agent for {:?}", self.character);
+        match self.character {
+            Character::None => None,
+            Character::Hovered(ref agent) | Character::Locked(ref agent) => {
+                agent_from_uuid(agents, agent).or_else(|| Some(Agent::unknown(agent)))
+            }
+        }
+    }
+}
+
+pub async fn get_match_info(
+    session: &sessions::Config,
+    entitlements: &entitlements::Config,
+    match_id: &str,
+    presences: &[crate::local::presence::Player],
+    http: &reqwest::Client,
+    limiter: &RateLimiter,
+) -> Result<MatchData> {
+    // check ingame first
+    let mut info = ingame::load_match_info(session, entitlements, match_id, http, limiter).await;
+    debug!("Ingame endpoint returned: {:#?}", info);
+
+    if info.is_err() {
+        // otherwise check pregame
+        info = pregame::load_match_info(session, entitlements, match_id, http, limiter).await;
+        debug!("Pregame endpoint returned: {:#?}", info);
+    }
+
+    let Ok(mut info) = info else {
+        return info; // bail if no match data
+    };
+
+    info.match_id = match_id.to_string();
+    info.assign_parties(presences);
+
+    Ok(info)
+}
+
+fn agent_from_uuid(agents_map: &[Agent], uuid: &str) -> Option<Agent> {
+    trace!(uuid = uuid, agents = ?agents_map, "Finding agent");
+    agents_map.iter().find(|a| a.uuid == uuid).cloned()
+}
@@ -0,0 +1,55 @@
+use crate::pvp::party::{CustomGameTeam, Party};
+
+impl From<&Party> for super::MatchData {
+    fn from(party: &Party) -> Self {
+        let membership = &party.custom_game_data.membership;
+        let settings = &party.custom_game_data.settings;
+
+        // custom lobbies don't have an agent select yet, so nobody has a character
+        let (players, spectators): (Vec<super::Player>, Vec<super::Player>) = party
+            .members
+            .iter()
+            .map(|member| {
+                let (team, role) = match membership.team_of(&member.puuid) {
+                    Some(CustomGameTeam::TeamOne) => (super::Team::Blue, super::Role::Player),
+                    Some(CustomGameTeam::TeamTwo) => (super::Team::Red, super::Role::Player),
+                    Some(CustomGameTeam::TeamOneCoaches) => (super::Team::Blue, super::Role::Coach),
+                    Some(CustomGameTeam::TeamTwoCoaches) => (super::Team::Red, super::Role::Coach),
+                    Some(CustomGameTeam::TeamSpectate) | None => {
+                        (super::Team::Neutral, super::Role::Observer)
+                    }
+                };
+
+                let identity = &member.player_identity;
+                super::Player {
+                    puuid: member.puuid.clone(),
+                    name: "".to_string(),
+                    team,
+                    character: super::Character::None,
+                    card: identity.card_id.clone(),
+                    title: identity.title_id.clone(),
+                    account_level: identity.account_level,
+                    border: identity.border_id.clone(),
+                    incognito: identity.incognito,
+                    hide_account_level: identity.hide_account_level,
+                    competitive_history: Vec::new(),
+                    party_id: party.id.clone(),
+                    role,
+                    incognito_index: None,
+                    status: super::PlayerStatus::default(),
+                }
+            })
+            .partition(|p: &super::Player| p.role == super::Role::Player);
+
+        let mut match_data = super::MatchData::new(
+            false,
+            settings.map.clone(),
+            settings.mode.clone(),
+            players,
+            spectators,
+        );
+        match_data.match_id = party.id.clone();
+
+        match_data
+    }
+}
@@ -0,0 +1,128 @@
+use color_eyre::{eyre::bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    local::{entitlements, sessions},
+    pvp::ratelimit::RateLimiter,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MatchInfo {
+    #[serde(rename = "MapID")]
+    map_id: String,
+    #[serde(rename = "ModeID")]
+    mode: String,
+    players: Vec<MatchPlayer>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MatchPlayer {
+    #[serde(rename = "Subject")]
+    puuid: String,
+    #[serde(rename = "TeamID")]
+    team: super::Team,
+    #[serde(rename = "CharacterID")]
+    character_id: String,
+    player_identity: PlayerIdentity,
+    is_coach: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PlayerIdentity {
+    #[serde(rename = "PlayerCardID")]
+    card_id: String,
+    #[serde(rename = "PlayerTitleID")]
+    title_id: String,
+    account_level: u32,
+    #[serde(rename = "PreferredLevelBorderID")]
+    border_id: String,
+    incognito: bool,
+    hide_account_level: bool,
+}
+
+impl MatchPlayer {
+    fn role(&self) -> super::Role {
+        match (self.is_coach, &self.team) {
+            (true, _) => super::Role::Coach,
+            (false, super::Team::Neutral) => super::Role::Observer,
+            _ => super::Role::Player,
+        }
+    }
+}
+
+impl From<MatchInfo> for super::MatchData {
+    fn from(value: MatchInfo) -> Self {
+        // coaches and observers come back in the same list as everyone else
+        let (players, spectators): (Vec<super::Player>, Vec<super::Player>) = value
+            .players
+            .into_iter()
+            .map(|p| p.into())
+            .partition(|p: &super::Player| p.role == super::Role::Player);
+
+        super::MatchData::new(true, value.map_id, value.mode, players, spectators)
+    }
+}
+
+// holy (?????)
+impl From<MatchPlayer> for super::Player {
+    fn from(value: MatchPlayer) -> Self {
+        let role = value.role();
+        let character = match role {
+            super::Role::Player => super::Character::Locked(value.character_id),
+            _ => super::Character::None,
+        };
+
+        super::Player {
+            puuid: value.puuid,
+            name: "".to_string(),
+            team: value.team,
+            character,
+            card: value.player_identity.card_id,
+            title: value.player_identity.title_id,
+            account_level: value.player_identity.account_level,
+            border: value.player_identity.border_id,
+            incognito: value.player_identity.incognito,
+            hide_account_level: value.player_identity.hide_account_level,
+            competitive_history: Vec::new(),
+            party_id: "".to_string(),
+            role,
+            incognito_index: None,
+            status: super::PlayerStatus::default(),
+        }
+    }
+}
+
+pub async fn load_match_info(
+    session: &sessions::Config,
+    entitlements: &entitlements::Config,
+    match_id: &str,
+    http: &reqwest::Client,
+    limiter: &RateLimiter,
+) -> Result<super::MatchData> {
+    // first check ingame
+    let endpoint = format!(
+        "{}/core-game/v1/matches/{match_id}",
+        crate::pvp::glz_url(session)
+    );
+
+    let info = limiter
+        .send(
+            http.get(endpoint)
+                .bearer_auth(&entitlements.token)
+                .header("X-Riot-Entitlements-JWT", &entitlements.jwt),
+        )
+        .await?;
+
+    if !info.status().is_success() {
+        bail!(
+            "Ingame match check failed with status code {}.",
+            info.status()
+        );
+    }
+
+    let info: MatchInfo = info.json().await?;
+    Ok(info.into())
+}
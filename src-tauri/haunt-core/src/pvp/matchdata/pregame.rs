@@ -0,0 +1,184 @@
+use color_eyre::{eyre::bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    local::{entitlements, sessions},
+    pvp::ratelimit::RateLimiter,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MatchInfo {
+    #[serde(rename = "MapID")]
+    map_id: String,
+    mode: String,
+    teams: Vec<MatchTeam>,
+    #[serde(default)]
+    observer_subjects: Vec<Subject>,
+    #[serde(default)]
+    match_coaches: Vec<Subject>,
+}
+
+/// Observers and coaches are only listed by subject in pregame, and Riot isn't consistent about
+/// whether that's a bare puuid or an object holding one.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(untagged)]
+enum Subject {
+    Puuid(String),
+    Object {
+        #[serde(rename = "Subject")]
+        puuid: String,
+    },
+}
+
+impl Subject {
+    fn into_player(self, role: super::Role) -> super::Player {
+        let puuid = match self {
+            Subject::Puuid(puuid) | Subject::Object { puuid } => puuid,
+        };
+
+        super::Player {
+            puuid,
+            name: "".to_string(),
+            team: super::Team::Neutral,
+            character: super::Character::None,
+            card: "".to_string(),
+            title: "".to_string(),
+            account_level: 0,
+            border: "".to_string(),
+            incognito: false,
+            hide_account_level: true,
+            competitive_history: Vec::new(),
+            party_id: "".to_string(),
+            role,
+            incognito_index: None,
+            status: super::PlayerStatus::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MatchTeam {
+    #[serde(rename = "TeamID")]
+    team_id: super::Team,
+    players: Vec<MatchPlayer>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MatchPlayer {
+    #[serde(rename = "Subject")]
+    puuid: String,
+    character_selection_state: CharacterSelectionState,
+    #[serde(rename = "CharacterID")]
+    character_id: String,
+    player_identity: PlayerIdentity,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+enum CharacterSelectionState {
+    Selected,
+    Locked,
+    #[serde(other)]
+    None,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PlayerIdentity {
+    #[serde(rename = "PlayerCardID")]
+    card_id: String,
+    #[serde(rename = "PlayerTitleID")]
+    title_id: String,
+    account_level: u32,
+    #[serde(rename = "PreferredLevelBorderID")]
+    border_id: String,
+    incognito: bool,
+    hide_account_level: bool,
+}
+
+impl From<MatchInfo> for super::MatchData {
+    fn from(value: MatchInfo) -> Self {
+        // SWEET MOTHER OF GOD
+        // captains are real players in custom tournament lobbies, so they stay on their team
+        let players = value
+            .teams
+            .iter()
+            .flat_map(|team| {
+                let team = team.clone();
+                team.players.into_iter().map(move |p| super::Player {
+                    puuid: p.puuid,
+                    name: "".to_string(),
+                    team: team.team_id.clone(),
+                    character: get_character(p.character_selection_state, p.character_id),
+                    card: p.player_identity.card_id,
+                    title: p.player_identity.title_id,
+                    account_level: p.player_identity.account_level,
+                    border: p.player_identity.border_id,
+                    incognito: p.player_identity.incognito,
+                    hide_account_level: p.player_identity.hide_account_level,
+                    competitive_history: Vec::new(),
+                    party_id: "".to_string(),
+                    role: super::Role::Player,
+                    incognito_index: None,
+                    status: super::PlayerStatus::default(),
+                })
+            })
+            .collect();
+
+        let spectators = value
+            .match_coaches
+            .into_iter()
+            .map(|s| s.into_player(super::Role::Coach))
+            .chain(
+                value
+                    .observer_subjects
+                    .into_iter()
+                    .map(|s| s.into_player(super::Role::Observer)),
+            )
+            .collect();
+
+        super::MatchData::new(false, value.map_id, value.mode, players, spectators)
+    }
+}
+
+fn get_character(state: CharacterSelectionState, character: String) -> super::Character {
+    match state {
+        CharacterSelectionState::Locked => super::Character::Locked(character),
+        CharacterSelectionState::Selected => super::Character::Hovered(character),
+        CharacterSelectionState::None => super::Character::None,
+    }
+}
+
+pub async fn load_match_info(
+    session: &sessions::Config,
+    entitlements: &entitlements::Config,
+    match_id: &str,
+    http: &reqwest::Client,
+    limiter: &RateLimiter,
+) -> Result<super::MatchData> {
+    // first check ingame
+    let endpoint = format!(
+        "{}/pregame/v1/matches/{match_id}",
+        crate::pvp::glz_url(session)
+    );
+
+    let info = limiter
+        .send(
+            http.get(endpoint)
+                .bearer_auth(&entitlements.token)
+                .header("X-Riot-Entitlements-JWT", &entitlements.jwt),
+        )
+        .await?;
+
+    if !info.status().is_success() {
+        bail!(
+            "Pregame match check failed with status code {}.",
+            info.status()
+        );
+    }
+
+    let info: MatchInfo = info.json().await?;
+    Ok(info.into())
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+
+use color_eyre::Result;
+use serde::Deserialize;
+
+use super::{entitlements, matchdata::Team, ratelimit::RateLimiter, sessions, CLIENT_PLATFORM};
+
+#[derive(Debug, Deserialize)]
+struct MatchDetailsResponse {
+    teams: Option<Vec<TeamResult>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TeamResult {
+    team_id: Team,
+    won: bool,
+}
+
+/// Loads whether each team won a finished match, keyed by team name.
+/// Only available a little while after the match ends.
+pub async fn get_match_results(
+    match_id: &str,
+    entitlements: &entitlements::Config,
+    http: &reqwest::Client,
+    limiter: &RateLimiter,
+    session: &sessions::Config,
+) -> Result<HashMap<String, bool>> {
+    let res = limiter
+        .send(
+            http.get(format!(
+                "{}/match-details/v1/matches/{match_id}",
+                super::pd_url(session)
+            ))
+            .bearer_auth(&entitlements.token)
+            .header("X-Riot-Entitlements-JWT", &entitlements.jwt)
+            .header("X-Riot-ClientPlatform", CLIENT_PLATFORM)
+            .header("X-Riot-ClientVersion", &session.version),
+        )
+        .await?
+        .error_for_status()?
+        .json::<MatchDetailsResponse>()
+        .await?;
+
+    let results = res
+        .teams
+        .unwrap_or_default()
+        .into_iter()
+        .map(|team| (team.team_id.to_string(), team.won))
+        .collect();
+
+    debug!("Results for {match_id}: {:#?}", results);
+
+    Ok(results)
+}
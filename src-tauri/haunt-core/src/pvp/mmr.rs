@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+
+use color_eyre::{eyre::bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::valapi::seasons::Season;
+
+use super::{
+    cache::{Endpoint, ResponseCache},
+    entitlements,
+    ratelimit::RateLimiter,
+    sessions, CLIENT_PLATFORM,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PlayerMMRResponse {
+    queue_skills: HashMap<String, QueueSkill>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QueueSkill {
+    #[serde(rename = "SeasonalInfoBySeasonID")]
+    seasonal_info_by_season_id: Option<HashMap<String, SeasonalInfoResponse>>,
+}
+
+// cached as-is, so it has to round trip
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct SeasonalInfoResponse {
+    // the fact that ID is in all caps is so incredibly annoying
+    #[serde(rename = "SeasonID")]
+    pub season_id: String,
+    pub competitive_tier: u32,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeasonalInfo {
+    pub episode_id: String,
+    pub season_id: String,
+    pub competitive_tier: u32,
+}
+
+pub type History = Vec<SeasonalInfo>;
+
+/// The player has never played competitive, so there's no history to load.
+#[derive(Debug, thiserror::Error)]
+#[error("No competitive history found for player.")]
+pub struct NoCompetitiveHistory;
+
+/// Loads the player's rank in each of the given acts. The raw mmr response is cached, so
+/// it's only refetched once the cache entry expires.
+pub async fn get_player_history(
+    puuid: &str,
+    http: &reqwest::Client,
+    limiter: &RateLimiter,
+    cache: &ResponseCache,
+    entitlements: &entitlements::Config,
+    session: &sessions::Config,
+    acts: &[Season],
+) -> Result<History> {
+    let mut competitive =
+        match cache.get::<HashMap<String, SeasonalInfoResponse>>(Endpoint::Mmr, puuid) {
+            Some(competitive) => competitive,
+            None => {
+                let competitive =
+                    fetch_competitive(puuid, http, limiter, entitlements, session).await?;
+                cache.insert(Endpoint::Mmr, puuid, &competitive);
+                competitive
+            }
+        };
+
+    let mut history = History::new();
+    for act in acts {
+        if let Some(act_info) = competitive.remove(&act.season_uuid) {
+            history.push(SeasonalInfo {
+                episode_id: act.competitive_tiers_uuid.clone(),
+                season_id: act_info.season_id,
+                competitive_tier: act_info.competitive_tier,
+            });
+        }
+    }
+
+    debug!("Loaded history for {puuid}: {:#?}", history);
+
+    Ok(history)
+}
+
+async fn fetch_competitive(
+    puuid: &str,
+    http: &reqwest::Client,
+    limiter: &RateLimiter,
+    entitlements: &entitlements::Config,
+    session: &sessions::Config,
+) -> Result<HashMap<String, SeasonalInfoResponse>> {
+    let endpoint = format!("{}/mmr/v1/players/{puuid}", super::pd_url(session));
+
+    let mut res = limiter
+        .send(
+            http.get(&endpoint)
+                .bearer_auth(&entitlements.token)
+                .header("X-Riot-Entitlements-JWT", &entitlements.jwt)
+                .header("X-Riot-ClientPlatform", CLIENT_PLATFORM)
+                .header("X-Riot-ClientVersion", &session.version),
+        )
+        .await?
+        .json::<PlayerMMRResponse>()
+        .await?;
+
+    // remove takes ownership of the thing!
+    match res.queue_skills.remove("competitive") {
+        Some(competitive) => Ok(competitive
+            .seasonal_info_by_season_id
+            .unwrap_or(HashMap::new())),
+        None => bail!(NoCompetitiveHistory),
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use color_eyre::eyre::Result;
+use serde::Deserialize;
+
+use crate::local::{entitlements, sessions};
+
+use super::{
+    cache::{Endpoint, ResponseCache},
+    matchdata::Player,
+    ratelimit::RateLimiter,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct NameServiceResponse {
+    /// puuid
+    subject: String,
+    game_name: String,
+    tag_line: String,
+}
+
+/// Fetches corresponding player name for each PUUID. Only players without a cached name are
+/// sent to the name service.
+///
+/// * `players` - The list of players to fetch names for, including coaches and observers.
+/// * `http` - The reqwest client to use for requests.
+/// * `limiter` - Rate limiter shared by every pd/glz request.
+/// * `cache` - Cache shared by every pd lookup.
+pub async fn load_player_names(
+    players: &[&Player],
+    session_config: &sessions::Config,
+    entitlements_config: &entitlements::Config,
+    http: &reqwest::Client,
+    limiter: &RateLimiter,
+    cache: &ResponseCache,
+) -> Result<HashMap<String, String>> {
+    let mut names = HashMap::new();
+    let mut uncached = Vec::new();
+
+    for player in players {
+        match cache.get::<String>(Endpoint::Names, &player.puuid) {
+            Some(name) => {
+                names.insert(player.puuid.clone(), name);
+            }
+            None => uncached.push(player.puuid.clone()),
+        }
+    }
+
+    if uncached.is_empty() {
+        debug!("Every player name was cached.");
+        return Ok(names);
+    }
+
+    let url = format!("{}/name-service/v2/players", super::pd_url(session_config));
+
+    let body = serde_json::to_string(&uncached)?;
+
+    let res = limiter
+        .send(
+            http.put(url)
+                .bearer_auth(&entitlements_config.token)
+                .header("X-Riot-Entitlements-JWT", &entitlements_config.jwt)
+                .body(body),
+        )
+        .await?
+        .json::<Vec<NameServiceResponse>>()
+        .await?;
+
+    debug!("Name service response: {:#?}", res);
+
+    res.iter().for_each(|p| {
+        let name = format!("{} #{}", p.game_name.clone(), p.tag_line.clone());
+        cache.insert(Endpoint::Names, &p.subject, &name);
+        names.insert(p.subject.clone(), name);
+    });
+
+    Ok(names)
+}
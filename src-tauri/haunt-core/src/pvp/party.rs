@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+
+use color_eyre::{eyre::bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{entitlements, ratelimit::RateLimiter, sessions, CLIENT_PLATFORM};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PartyPlayerResponse {
+    #[serde(rename = "CurrentPartyID")]
+    current_party_id: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Party {
+    #[serde(rename = "ID")]
+    pub id: String,
+    pub state: PartyState,
+    pub members: Vec<PartyMember>,
+    pub custom_game_data: CustomGameData,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub enum PartyState {
+    #[serde(rename = "CustomGameSetup")]
+    CustomGameSetup,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PartyMember {
+    #[serde(rename = "Subject")]
+    pub puuid: String,
+    pub player_identity: PlayerIdentity,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PlayerIdentity {
+    #[serde(rename = "PlayerCardID")]
+    pub card_id: String,
+    #[serde(rename = "PlayerTitleID")]
+    pub title_id: String,
+    pub account_level: u32,
+    #[serde(rename = "PreferredLevelBorderID")]
+    pub border_id: String,
+    pub incognito: bool,
+    pub hide_account_level: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CustomGameData {
+    pub settings: CustomGameSettings,
+    pub membership: Membership,
+}
+
+/// Sent back as-is when changing settings, so every field we don't touch has to round trip.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct CustomGameSettings {
+    pub map: String,
+    pub mode: String,
+    #[serde(default)]
+    pub use_bots: bool,
+    #[serde(default)]
+    pub game_pod: String,
+    /// rule values are stringified bools, e.g. `"AllowGameModifiers": "true"`
+    #[serde(default)]
+    pub game_rules: Option<HashMap<String, String>>,
+}
+
+impl CustomGameSettings {
+    fn rule(&self, name: &str) -> bool {
+        self.game_rules
+            .as_ref()
+            .and_then(|rules| rules.get(name))
+            .is_some_and(|value| value == "true")
+    }
+
+    /// Whether cheats (game modifiers) are enabled.
+    pub fn cheats(&self) -> bool {
+        self.rule("AllowGameModifiers")
+    }
+
+    /// Whether tournament mode is enabled.
+    pub fn tournament_mode(&self) -> bool {
+        self.rule("TournamentMode")
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Membership {
+    #[serde(default)]
+    pub team_one: Option<Vec<MembershipSubject>>,
+    #[serde(default)]
+    pub team_two: Option<Vec<MembershipSubject>>,
+    #[serde(default)]
+    pub team_spectate: Option<Vec<MembershipSubject>>,
+    #[serde(default)]
+    pub team_one_coaches: Option<Vec<MembershipSubject>>,
+    #[serde(default)]
+    pub team_two_coaches: Option<Vec<MembershipSubject>>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct MembershipSubject {
+    #[serde(rename = "Subject")]
+    pub puuid: String,
+}
+
+impl Membership {
+    /// Returns the custom game team the player has been assigned to, if any.
+    pub fn team_of(&self, puuid: &str) -> Option<CustomGameTeam> {
+        let teams = [
+            (&self.team_one, CustomGameTeam::TeamOne),
+            (&self.team_two, CustomGameTeam::TeamTwo),
+            (&self.team_spectate, CustomGameTeam::TeamSpectate),
+            (&self.team_one_coaches, CustomGameTeam::TeamOneCoaches),
+            (&self.team_two_coaches, CustomGameTeam::TeamTwoCoaches),
+        ];
+
+        teams.into_iter().find_map(|(members, team)| {
+            members
+                .as_ref()?
+                .iter()
+                .any(|m| m.puuid == puuid)
+                .then_some(team)
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CustomGameTeam {
+    TeamOne,
+    TeamTwo,
+    TeamSpectate,
+    TeamOneCoaches,
+    TeamTwoCoaches,
+}
+
+impl std::fmt::Display for CustomGameTeam {
+    // this is the casing the membership endpoint expects in its path
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            CustomGameTeam::TeamOne => "TeamOne",
+            CustomGameTeam::TeamTwo => "TeamTwo",
+            CustomGameTeam::TeamSpectate => "TeamSpectate",
+            CustomGameTeam::TeamOneCoaches => "TeamOneCoaches",
+            CustomGameTeam::TeamTwoCoaches => "TeamTwoCoaches",
+        })
+    }
+}
+
+fn parties_url(session: &sessions::Config) -> String {
+    format!("{}/parties/v1", super::glz_url(session))
+}
+
+/// Loads the party the current user is in.
+pub async fn get_party(
+    entitlements: &entitlements::Config,
+    http: &reqwest::Client,
+    limiter: &RateLimiter,
+    session: &sessions::Config,
+) -> Result<Party> {
+    let base = parties_url(session);
+
+    let party_id = limiter
+        .send(
+            http.get(format!("{base}/players/{}", session.puuid))
+                .bearer_auth(&entitlements.token)
+                .header("X-Riot-Entitlements-JWT", &entitlements.jwt)
+                .header("X-Riot-ClientPlatform", CLIENT_PLATFORM)
+                .header("X-Riot-ClientVersion", &session.version),
+        )
+        .await?
+        .json::<PartyPlayerResponse>()
+        .await?
+        .current_party_id;
+
+    let party = limiter
+        .send(
+            http.get(format!("{base}/parties/{party_id}"))
+                .bearer_auth(&entitlements.token)
+                .header("X-Riot-Entitlements-JWT", &entitlements.jwt)
+                .header("X-Riot-ClientPlatform", CLIENT_PLATFORM)
+                .header("X-Riot-ClientVersion", &session.version),
+        )
+        .await?
+        .json::<Party>()
+        .await?;
+
+    debug!("Party: {:#?}", party);
+
+    Ok(party)
+}
+
+/// Returns the user's party if it's currently setting up a custom game.
+pub async fn find_custom_game(
+    entitlements: &entitlements::Config,
+    http: &reqwest::Client,
+    limiter: &RateLimiter,
+    session: &sessions::Config,
+) -> Option<Party> {
+    debug!("Checking party api for a custom game lobby...");
+
+    match get_party(entitlements, http, limiter, session).await {
+        Ok(party) if party.state == PartyState::CustomGameSetup => {
+            debug!("Player is in a custom game lobby.");
+            Some(party)
+        }
+        Ok(_) => {
+            debug!("Player's party is not in a custom game lobby.");
+            None
+        }
+        Err(why) => {
+            warn!("Unable to load party: {why}");
+            None
+        }
+    }
+}
+
+/// Replaces the custom game settings for the party. Only the party owner can do this.
+pub async fn set_custom_game_settings(
+    party_id: &str,
+    settings: &CustomGameSettings,
+    entitlements: &entitlements::Config,
+    http: &reqwest::Client,
+    limiter: &RateLimiter,
+    session: &sessions::Config,
+) -> Result<()> {
+    let res = limiter
+        .send(
+            http.post(format!(
+                "{}/parties/{party_id}/customgamesettings",
+                parties_url(session)
+            ))
+            .bearer_auth(&entitlements.token)
+            .header("X-Riot-Entitlements-JWT", &entitlements.jwt)
+            .header("X-Riot-ClientPlatform", CLIENT_PLATFORM)
+            .header("X-Riot-ClientVersion", &session.version)
+            .json(settings),
+        )
+        .await?;
+
+    if !res.status().is_success() {
+        bail!(
+            "Updating custom game settings failed with status code {}.",
+            res.status()
+        );
+    }
+
+    Ok(())
+}
+
+/// Moves a player to a different custom game team.
+pub async fn move_to_team(
+    party_id: &str,
+    puuid: &str,
+    team: CustomGameTeam,
+    entitlements: &entitlements::Config,
+    http: &reqwest::Client,
+    limiter: &RateLimiter,
+    session: &sessions::Config,
+) -> Result<()> {
+    let res = limiter
+        .send(
+            http.post(format!(
+                "{}/parties/{party_id}/customgamemembership/{}",
+                parties_url(session),
+                team
+            ))
+            .bearer_auth(&entitlements.token)
+            .header("X-Riot-Entitlements-JWT", &entitlements.jwt)
+            .header("X-Riot-ClientPlatform", CLIENT_PLATFORM)
+            .header("X-Riot-ClientVersion", &session.version)
+            .json(&serde_json::json!({ "playerToPutOnTeam": puuid })),
+        )
+        .await?;
+
+    if !res.status().is_success() {
+        bail!(
+            "Moving player to {} failed with status code {}.",
+            team.to_string(),
+            res.status()
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use reqwest::{header::RETRY_AFTER, RequestBuilder, Response, StatusCode};
+
+/// How many times a request is retried after being rate limited.
+const MAX_RETRIES: u32 = 3;
+/// Used when a 429 doesn't come with a usable Retry-After header. Multiplied by the attempt.
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Shared between every pd/glz request so a 429 on one host holds back everything else going to
+/// that host, not just the request that got throttled.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    blocked_until: Mutex<HashMap<String, Instant>>,
+}
+
+impl RateLimiter {
+    fn wait_time(&self, host: &str) -> Option<Duration> {
+        let blocked_until = self.blocked_until.lock().unwrap();
+        blocked_until
+            .get(host)
+            .and_then(|until| until.checked_duration_since(Instant::now()))
+    }
+
+    fn block(&self, host: &str, duration: Duration) {
+        let until = Instant::now() + duration;
+
+        let mut blocked_until = self.blocked_until.lock().unwrap();
+        let entry = blocked_until.entry(host.to_string()).or_insert(until);
+        // never shorten a backoff another request already set
+        if *entry < until {
+            *entry = until;
+        }
+    }
+
+    /// Sends the request once its host isn't backing off, retrying on 429.
+    ///
+    /// * `request` - The request to send. The client it was built from is used to send it.
+    pub async fn send(&self, request: RequestBuilder) -> reqwest::Result<Response> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        let host = request.url().host_str().unwrap_or_default().to_string();
+
+        let mut attempt = 0;
+        loop {
+            if let Some(wait) = self.wait_time(&host) {
+                debug!("Waiting {:?} before sending to {host}.", wait);
+                tokio::time::sleep(wait).await;
+            }
+
+            // streamed bodies can't be cloned, so those only get the one shot
+            let Some(next) = request.try_clone() else {
+                return crate::traffic::execute(&client, request).await;
+            };
+
+            let res = crate::traffic::execute(&client, next).await?;
+            if res.status() != StatusCode::TOO_MANY_REQUESTS || attempt >= MAX_RETRIES {
+                return Ok(res);
+            }
+
+            attempt += 1;
+            let backoff = retry_after(&res).unwrap_or(DEFAULT_BACKOFF * attempt);
+            warn!(
+                "Rate limited by {host}. Backing off for {:?} (attempt {attempt}/{MAX_RETRIES}).",
+                backoff
+            );
+            self.block(&host, backoff);
+        }
+    }
+}
+
+fn retry_after(res: &Response) -> Option<Duration> {
+    let seconds = res.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    seconds.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
@@ -0,0 +1,414 @@
+//! User settings, saved as versioned JSON. Older files are migrated as they're loaded, and
+//! anything missing falls back to its default, so a file only needs the settings that changed.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+};
+
+use color_eyre::eyre::{bail, eyre, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    local::sessions::{self, Region, Shard},
+    source::Backend,
+    watcher::PollIntervals,
+};
+
+/// Bumped whenever a setting is renamed, moved or changes meaning. Add a step to [`MIGRATIONS`]
+/// when it is.
+pub const VERSION: u32 = 1;
+
+/// Upgrades a file by one version. Indexed by the version it upgrades from.
+const MIGRATIONS: [fn(&mut Map<String, Value>); VERSION as usize] = [
+    // files from before versioning are the same shape, they just lack the version
+    |_| (),
+];
+
+/// Languages ValAPI has agent, map and rank names in.
+pub const LANGUAGES: [&str; 18] = [
+    "ar-AE", "de-DE", "en-US", "es-ES", "es-MX", "fr-FR", "id-ID", "it-IT", "ja-JP", "ko-KR",
+    "pl-PL", "pt-BR", "ru-RU", "th-TH", "tr-TR", "vi-VN", "zh-CN", "zh-TW",
+];
+
+/// Polling faster than this runs into pd/glz rate limits.
+const MIN_POLL_INTERVAL: u64 = 500;
+const MAX_POLL_INTERVAL: u64 = 10 * 60 * 1000;
+/// Riot only keeps a few episodes of competitive history.
+pub const MAX_RANK_HISTORY_ACTS: usize = 12;
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Settings {
+    pub version: u32,
+    /// how often the background watcher polls in each state
+    pub poll_intervals: PollIntervals,
+    /// acts of rank history shown per player, the current one included
+    pub rank_history_acts: usize,
+    /// overrides the region from the client's launch args, for accounts that were moved
+    pub region: Option<Region>,
+    /// overrides the shard the same way
+    pub shard: Option<Shard>,
+    /// language of agent, map and rank names. one of [`LANGUAGES`]
+    pub language: String,
+    pub privacy: Privacy,
+    pub enrichments: Enrichments,
+    /// tell the frontend whenever a friend starts or leaves a match, queue etc.
+    pub friend_notifications: bool,
+    /// where lobbies come from. `HAUNT_REPLAY` and `HAUNT_BACKEND` take priority over this.
+    /// a recording that's gone missing leaves the current source in place
+    pub data_source: Backend,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            version: VERSION,
+            poll_intervals: PollIntervals::default(),
+            // the last 3 acts plus the current one
+            rank_history_acts: 4,
+            region: None,
+            shard: None,
+            language: "en-US".to_string(),
+            privacy: Privacy::default(),
+            enrichments: Enrichments::default(),
+            friend_notifications: false,
+            data_source: Backend::default(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Privacy {
+    /// mask the names and account levels of everyone outside the user's party
+    pub streamer_mode: bool,
+    /// also hide the user's own Riot ID while streamer mode is on
+    pub hide_own_name: bool,
+    /// remember who the user played with, their names and how the match went
+    pub record_encounters: bool,
+}
+
+impl Default for Privacy {
+    fn default() -> Self {
+        Privacy {
+            streamer_mode: false,
+            hide_own_name: false,
+            record_encounters: true,
+        }
+    }
+}
+
+/// Extra data fetched or looked up for each match. Everything is on by default.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Enrichments {
+    /// Riot IDs from the name service
+    pub names: bool,
+    /// competitive history from the mmr service
+    pub rank_history: bool,
+    /// previous matches with each player, and their old names
+    pub encounters: bool,
+    /// wins and losses from the match history service once a match ends
+    pub match_results: bool,
+}
+
+impl Default for Enrichments {
+    fn default() -> Self {
+        Enrichments {
+            names: true,
+            rank_history: true,
+            encounters: true,
+            match_results: true,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SettingsError {
+    /// the field is named the way it's serialized, so the frontend can point at it
+    #[error("{field} {reason}")]
+    Invalid { field: String, reason: String },
+    #[error("Unable to save settings: {reason}")]
+    Save { reason: String },
+}
+
+/// Settings written by a newer Haunt, which might mean something different to this one.
+#[derive(Debug, thiserror::Error)]
+#[error("Settings are from a newer version of Haunt ({0}).")]
+struct NewerVersion(u32);
+
+fn invalid(field: &str, reason: impl Into<String>) -> SettingsError {
+    SettingsError::Invalid {
+        field: field.to_string(),
+        reason: reason.into(),
+    }
+}
+
+impl Settings {
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if self.version != VERSION {
+            return Err(invalid("version", format!("must be {VERSION}")));
+        }
+
+        let intervals = &self.poll_intervals;
+        for (field, interval) in [
+            ("pollIntervals.offline", intervals.offline),
+            ("pollIntervals.menus", intervals.menus),
+            ("pollIntervals.queue", intervals.queue),
+            ("pollIntervals.pregame", intervals.pregame),
+            ("pollIntervals.ingame", intervals.ingame),
+            ("pollIntervals.postGame", intervals.post_game),
+        ] {
+            if !(MIN_POLL_INTERVAL..=MAX_POLL_INTERVAL).contains(&interval) {
+                return Err(invalid(
+                    field,
+                    format!("must be between {MIN_POLL_INTERVAL} and {MAX_POLL_INTERVAL} ms"),
+                ));
+            }
+        }
+
+        if !(1..=MAX_RANK_HISTORY_ACTS).contains(&self.rank_history_acts) {
+            return Err(invalid(
+                "rankHistoryActs",
+                format!("must be between 1 and {MAX_RANK_HISTORY_ACTS}"),
+            ));
+        }
+
+        if !LANGUAGES.contains(&self.language.as_str()) {
+            return Err(invalid("language", "isn't a language ValAPI supports"));
+        }
+
+        Ok(())
+    }
+
+    /// Clamps out of range settings and puts unknown ones back to their default, so a bad value
+    /// doesn't cost the user every other setting. Returns the fields that were changed.
+    fn repair(&mut self) -> Vec<&'static str> {
+        let mut repaired = Vec::new();
+
+        let intervals = &mut self.poll_intervals;
+        for (field, interval) in [
+            ("pollIntervals.offline", &mut intervals.offline),
+            ("pollIntervals.menus", &mut intervals.menus),
+            ("pollIntervals.queue", &mut intervals.queue),
+            ("pollIntervals.pregame", &mut intervals.pregame),
+            ("pollIntervals.ingame", &mut intervals.ingame),
+            ("pollIntervals.postGame", &mut intervals.post_game),
+        ] {
+            let clamped = (*interval).clamp(MIN_POLL_INTERVAL, MAX_POLL_INTERVAL);
+            if clamped != *interval {
+                *interval = clamped;
+                repaired.push(field);
+            }
+        }
+
+        let acts = self.rank_history_acts.clamp(1, MAX_RANK_HISTORY_ACTS);
+        if acts != self.rank_history_acts {
+            self.rank_history_acts = acts;
+            repaired.push("rankHistoryActs");
+        }
+
+        if !LANGUAGES.contains(&self.language.as_str()) {
+            self.language = Settings::default().language;
+            repaired.push("language");
+        }
+
+        repaired
+    }
+
+    /// Applies the region and shard overrides to a freshly loaded session.
+    pub fn apply_overrides(&self, session: &mut sessions::Config) {
+        if let Some(region) = &self.region {
+            session.region = region.clone();
+        }
+        if let Some(shard) = &self.shard {
+            session.shard = shard.clone();
+        }
+    }
+}
+
+/// Drops the settings that can't be read, e.g. a number where there should be a list, so they
+/// fall back to their default alone. Returns the fields that were dropped.
+fn drop_unreadable(settings: &mut Map<String, Value>) -> Vec<String> {
+    let mut dropped = Vec::new();
+
+    // anything missing is defaulted, so each setting can be tried on its own
+    settings.retain(|field, value| {
+        let alone = Map::from_iter([(field.clone(), value.clone())]);
+        let readable = serde_json::from_value::<Settings>(Value::Object(alone)).is_ok();
+        if !readable {
+            dropped.push(field.clone());
+        }
+        readable
+    });
+
+    dropped
+}
+
+/// Reads a settings file, migrating it to the current version. Settings that can't be read or
+/// are invalid are defaulted or clamped, and returned by name.
+pub fn load(path: &Path) -> Result<(Settings, Vec<String>)> {
+    let file = fs::read_to_string(path)?;
+    let Value::Object(mut settings) = serde_json::from_str::<Value>(&file)? else {
+        bail!("Settings aren't a JSON object.");
+    };
+
+    let version = match settings.get("version") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .ok_or_else(|| eyre!("Settings version isn't a number."))?
+            as u32,
+    };
+    if version > VERSION {
+        bail!(NewerVersion(version));
+    }
+
+    for (from, migrate) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        info!("Migrating settings from version {from}.");
+        migrate(&mut settings);
+    }
+    settings.insert("version".to_string(), VERSION.into());
+
+    let mut repaired = drop_unreadable(&mut settings);
+    let mut settings = serde_json::from_value::<Settings>(Value::Object(settings))?;
+    repaired.extend(settings.repair().into_iter().map(str::to_string));
+    settings.validate()?;
+
+    Ok((settings, repaired))
+}
+
+/// Copies the file to `settings.json.bak`, before anything is written over it.
+fn back_up(path: &Path) {
+    let backup = path.with_extension("json.bak");
+    match fs::copy(path, &backup) {
+        Ok(_) => info!("Backed up settings to {}.", backup.display()),
+        Err(why) => warn!("Unable to back up settings to {}: {why}", backup.display()),
+    }
+}
+
+fn save(path: &Path, settings: &Settings) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    // written next to the real file and moved over it, so a crash never leaves half a file
+    let temp = path.with_extension("json.tmp");
+    fs::write(&temp, serde_json::to_string_pretty(settings)?)?;
+    fs::rename(&temp, path)?;
+
+    Ok(())
+}
+
+/// Holds the current [`Settings`]. Like `ContentStore`, readers get a snapshot.
+#[derive(Debug, Default)]
+pub struct SettingsStore {
+    settings: RwLock<Arc<Settings>>,
+    path: Mutex<Option<PathBuf>>,
+}
+
+impl SettingsStore {
+    /// Loads settings saved by a previous run and saves to the same file from then on.
+    /// Invalid settings are defaulted or clamped, and defaults are used if the file is missing or
+    /// broken. Either way the file is backed up before it's next saved. A file from a newer
+    /// version of Haunt is never overwritten.
+    pub fn load(&self, path: PathBuf) {
+        match load(&path) {
+            Ok((settings, repaired)) => {
+                if !repaired.is_empty() {
+                    warn!("Reset invalid settings: {}", repaired.join(", "));
+                    back_up(&path);
+                }
+
+                debug!("Loaded settings: {:#?}", settings);
+                *self.settings.write().unwrap() = Arc::new(settings);
+            }
+            Err(why) if path.exists() => {
+                warn!("Unable to load settings from {}: {why}", path.display());
+                if why.downcast_ref::<NewerVersion>().is_some() {
+                    return;
+                }
+                back_up(&path);
+            }
+            Err(_) => info!("No settings at {}. Using defaults.", path.display()),
+        }
+
+        *self.path.lock().unwrap() = Some(path);
+    }
+
+    pub fn get(&self) -> Arc<Settings> {
+        self.settings.read().unwrap().clone()
+    }
+
+    /// Validates and saves the settings, then swaps them in. Returns the previous settings.
+    pub fn set(&self, settings: Settings) -> Result<Arc<Settings>, SettingsError> {
+        settings.validate()?;
+
+        if let Some(path) = &*self.path.lock().unwrap() {
+            save(path, &settings).map_err(|why| SettingsError::Save {
+                reason: why.to_string(),
+            })?;
+        }
+
+        let previous = std::mem::replace(&mut *self.settings.write().unwrap(), Arc::new(settings));
+        Ok(previous)
+    }
+
+    /// Goes back to the defaults. Returns the previous settings.
+    pub fn reset(&self) -> Result<Arc<Settings>, SettingsError> {
+        self.set(Settings::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn unreadable_settings_are_dropped_alone() {
+        let Value::Object(mut settings) = json!({
+            "rankHistoryActs": "four",
+            "language": "de-DE",
+            "privacy": { "streamerMode": true },
+        }) else {
+            unreachable!();
+        };
+
+        assert_eq!(drop_unreadable(&mut settings), ["rankHistoryActs"]);
+
+        let settings = serde_json::from_value::<Settings>(Value::Object(settings)).unwrap();
+        assert_eq!(
+            settings.rank_history_acts,
+            Settings::default().rank_history_acts
+        );
+        assert_eq!(settings.language, "de-DE");
+        assert!(settings.privacy.streamer_mode);
+    }
+
+    #[test]
+    fn out_of_range_settings_are_clamped() {
+        let mut settings = Settings {
+            rank_history_acts: 50,
+            language: "xx-XX".to_string(),
+            ..Default::default()
+        };
+        settings.poll_intervals.ingame = 1;
+        settings.poll_intervals.menus = 7_000;
+
+        assert_eq!(
+            settings.repair(),
+            ["pollIntervals.ingame", "rankHistoryActs", "language"]
+        );
+        assert!(settings.validate().is_ok());
+        assert_eq!(settings.poll_intervals.ingame, MIN_POLL_INTERVAL);
+        assert_eq!(settings.poll_intervals.menus, 7_000);
+        assert_eq!(settings.rank_history_acts, MAX_RANK_HISTORY_ACTS);
+        assert_eq!(settings.language, "en-US");
+    }
+}
@@ -0,0 +1,179 @@
+//! Where match data comes from. Everything that loads a lobby goes through the [`DataSource`]
+//! held by the [`Client`], so the game can be swapped for a recording or a demo lobby at runtime,
+//! e.g. for frontend work and screenshots.
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+
+use async_trait::async_trait;
+use color_eyre::eyre::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    local::{entitlements, friends::Friend, presence, sessions},
+    lockfile,
+    login::{Login, LoginFail},
+    pvp::{
+        matchdata::{MatchData, Player},
+        mmr::History,
+    },
+    traffic,
+    valapi::seasons::Season,
+    Client,
+};
+
+mod demo;
+mod live;
+mod replay;
+
+pub use demo::Demo;
+pub use live::Live;
+pub use replay::Replay;
+
+/// The operations Haunt needs to show a lobby. Requests and caching go through `client`, so a
+/// source itself holds no connections.
+#[async_trait]
+pub trait DataSource: std::fmt::Debug + Send + Sync {
+    /// Finds the logged in user. Err signifies the stage login failed at.
+    async fn login(&self, client: &Client) -> Result<Login, LoginFail>;
+
+    /// Presences of the user and everyone they can see, i.e. friends and their party.
+    async fn presences(
+        &self,
+        client: &Client,
+        lockfile: &lockfile::Config,
+    ) -> Result<Vec<presence::Player>>;
+
+    /// Everyone on the user's friends list, with their chat status.
+    async fn friends(&self, client: &Client, lockfile: &lockfile::Config) -> Result<Vec<Friend>>;
+
+    /// The match the user is in, pregame or ingame.
+    async fn find_match(
+        &self,
+        client: &Client,
+        entitlements: &entitlements::Config,
+        session: &sessions::Config,
+    ) -> Option<String>;
+
+    /// Teams, agents and parties in the match. Names and ranks aren't filled in.
+    async fn match_info(
+        &self,
+        client: &Client,
+        entitlements: &entitlements::Config,
+        session: &sessions::Config,
+        match_id: &str,
+        presences: &[presence::Player],
+    ) -> Result<MatchData>;
+
+    /// Riot IDs for each player, keyed by PUUID.
+    async fn names(
+        &self,
+        client: &Client,
+        entitlements: &entitlements::Config,
+        session: &sessions::Config,
+        players: &[&Player],
+    ) -> Result<HashMap<String, String>>;
+
+    /// The player's rank in each of `acts`.
+    async fn mmr(
+        &self,
+        client: &Client,
+        entitlements: &entitlements::Config,
+        session: &sessions::Config,
+        puuid: &str,
+        acts: &[Season],
+    ) -> Result<History>;
+
+    /// Every past competitive act, most recent first.
+    async fn seasons(&self, client: &Client) -> Result<Vec<Season>>;
+}
+
+/// Which [`DataSource`] to use. Saved and sent to the frontend as a setting.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Backend {
+    /// the running game
+    #[default]
+    Live,
+    /// a recording made with [`traffic::start_recording`]
+    Replay { recording: PathBuf },
+    /// a made up lobby that loops through the game on a timer
+    Demo,
+}
+
+impl Backend {
+    /// `HAUNT_REPLAY=<recording>` or `HAUNT_BACKEND=demo`. Live if neither is set.
+    pub fn from_env() -> Self {
+        match std::env::var_os("HAUNT_REPLAY") {
+            Some(recording) if !recording.is_empty() => Backend::Replay {
+                recording: recording.into(),
+            },
+            _ => match std::env::var("HAUNT_BACKEND").as_deref() {
+                Ok("demo") => Backend::Demo,
+                _ => Backend::Live,
+            },
+        }
+    }
+
+    /// Checks the backend can be switched to, i.e. that a recording can be read and nothing is
+    /// being recorded, without switching to it.
+    pub fn validate(&self) -> Result<()> {
+        if let Backend::Replay { recording } = self {
+            if traffic::is_recording() {
+                bail!("Stop recording before replaying.");
+            }
+            traffic::Replay::load(recording)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Holds the current [`DataSource`]. Like `ContentStore`, readers get a snapshot, so a switch
+/// never happens halfway through loading a match.
+#[derive(Debug)]
+pub struct SourceStore(RwLock<(Backend, Arc<dyn DataSource>)>);
+
+impl Default for SourceStore {
+    fn default() -> Self {
+        SourceStore(RwLock::new((Backend::Live, Arc::new(Live))))
+    }
+}
+
+impl SourceStore {
+    pub fn get(&self) -> Arc<dyn DataSource> {
+        self.0.read().unwrap().1.clone()
+    }
+
+    pub fn backend(&self) -> Backend {
+        self.0.read().unwrap().0.clone()
+    }
+
+    /// Switches to `backend`. The current source is kept if the new one can't be built, e.g. if
+    /// the recording can't be read or something is being recorded.
+    pub fn set(&self, backend: Backend) -> Result<()> {
+        let (source, replay): (Arc<dyn DataSource>, _) = match &backend {
+            Backend::Live => (Arc::new(Live), None),
+            Backend::Replay { recording } => (
+                Arc::new(Replay::new(recording)),
+                Some(traffic::Replay::load(recording)?),
+            ),
+            Backend::Demo => (Arc::new(Demo::default()), None),
+        };
+
+        // replays answer requests process wide, so they're only started or stopped while nobody
+        // can get hold of either source
+        let mut current = self.0.write().unwrap();
+        match replay {
+            Some(replay) => traffic::start_replay(replay)?,
+            None => traffic::stop_replay(),
+        }
+        *current = (backend, source);
+        info!("Switched data source to {:?}.", current.0);
+
+        Ok(())
+    }
+}
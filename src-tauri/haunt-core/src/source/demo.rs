@@ -0,0 +1,495 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use color_eyre::eyre::{bail, Result};
+
+use super::{DataSource, Live};
+use crate::{
+    local::{
+        entitlements,
+        friends::Friend,
+        presence::{self, Availability, Private},
+        sessions::{self, Region, Shard},
+    },
+    lockfile,
+    login::{Login, LoginFail},
+    pvp::{
+        matchdata::{Character, MatchData, Player, Team},
+        mmr::{History, SeasonalInfo},
+    },
+    valapi::seasons::Season,
+    Client,
+};
+
+const MODE: &str = "/Game/GameModes/Bomb/BombGameMode.BombGameMode_C";
+/// each loop of the demo is played on the next map
+const MAPS: [&str; 5] = [
+    "/Game/Maps/Ascent/Ascent",
+    "/Game/Maps/Bonsai/Bonsai",
+    "/Game/Maps/Triad/Triad",
+    "/Game/Maps/Duality/Duality",
+    "/Game/Maps/Port/Port",
+];
+const AGENTS: [&str; 10] = [
+    "add6443a-41bd-e414-f6ad-e58d267f4e95", // jett
+    "320b2a48-4d9b-a075-30f1-1f93a9b638fa", // sova
+    "569fdd95-4d10-43ab-ca70-79becc718b46", // sage
+    "8e253930-4c05-31dd-1b6c-968525494517", // omen
+    "1e58de9c-4950-5125-93e9-a0aee9f98746", // killjoy
+    "a3bfb853-43b2-7238-a4f1-ad90e9e46bcc", // reyna
+    "eb93336a-449b-9c1b-0a54-a891f7921d69", // phoenix
+    "9f0d8ba9-4140-b941-57d3-a7ad57c6b417", // brimstone
+    "117ed9e3-49f3-6512-3ccf-0cada7e3823b", // cypher
+    "f94c3b30-42be-e959-889c-5aa313dba261", // raze
+];
+
+struct DemoPlayer {
+    name: &'static str,
+    tag: &'static str,
+    account_level: u32,
+    /// tier in each act, most recent first. 0 means they didn't play it
+    ranks: [u32; 4],
+    incognito: bool,
+    hide_account_level: bool,
+    /// only the user's party and friends have presences, so only they get party IDs
+    party: Option<&'static str>,
+}
+
+/// The user is first. The first five are on blue, the rest on red.
+const ROSTER: [DemoPlayer; 10] = [
+    DemoPlayer {
+        name: "Haunt",
+        tag: "demo",
+        account_level: 142,
+        ranks: [16, 15, 15, 14],
+        incognito: false,
+        hide_account_level: false,
+        party: Some("demo-party"),
+    },
+    DemoPlayer {
+        name: "Lantern",
+        tag: "2718",
+        account_level: 87,
+        ranks: [14, 13, 0, 12],
+        incognito: false,
+        hide_account_level: false,
+        party: Some("demo-party"),
+    },
+    DemoPlayer {
+        name: "Wisp",
+        tag: "0451",
+        account_level: 203,
+        ranks: [17, 17, 16, 16],
+        incognito: false,
+        hide_account_level: true,
+        party: Some("demo-party"),
+    },
+    DemoPlayer {
+        name: "Cobweb",
+        tag: "EUW",
+        account_level: 55,
+        ranks: [12, 11, 0, 0],
+        incognito: true,
+        hide_account_level: false,
+        party: None,
+    },
+    DemoPlayer {
+        name: "Banshee",
+        tag: "wail",
+        account_level: 310,
+        ranks: [18, 19, 18, 18],
+        incognito: false,
+        hide_account_level: false,
+        party: None,
+    },
+    DemoPlayer {
+        name: "Poltergeist",
+        tag: "boo",
+        account_level: 174,
+        ranks: [15, 16, 15, 0],
+        incognito: false,
+        hide_account_level: false,
+        party: None,
+    },
+    DemoPlayer {
+        name: "Revenant",
+        tag: "1337",
+        account_level: 98,
+        ranks: [16, 14, 14, 13],
+        incognito: true,
+        hide_account_level: true,
+        party: None,
+    },
+    DemoPlayer {
+        name: "Shade",
+        tag: "NA1",
+        account_level: 421,
+        ranks: [19, 18, 18, 17],
+        incognito: false,
+        hide_account_level: false,
+        party: Some("demo-friend-party"),
+    },
+    DemoPlayer {
+        name: "Spectre",
+        tag: "0007",
+        account_level: 12,
+        ranks: [0, 0, 0, 0],
+        incognito: false,
+        hide_account_level: false,
+        party: None,
+    },
+    DemoPlayer {
+        name: "Wraith",
+        tag: "moan",
+        account_level: 260,
+        ranks: [15, 15, 16, 17],
+        incognito: true,
+        hide_account_level: false,
+        party: None,
+    },
+];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Phase {
+    Menus,
+    Queue,
+    Pregame,
+    Ingame,
+    PostGame,
+}
+
+/// How long each phase lasts, in order.
+const PHASES: [(Phase, Duration); 5] = [
+    (Phase::Menus, Duration::from_secs(8)),
+    (Phase::Queue, Duration::from_secs(12)),
+    (Phase::Pregame, Duration::from_secs(40)),
+    (Phase::Ingame, Duration::from_secs(60)),
+    (Phase::PostGame, Duration::from_secs(10)),
+];
+
+/// A made up competitive lobby that loops from the menus through queue, agent select and the
+/// game on a timer, with a new match and map each loop. No game or network is needed, apart from
+/// ValAPI for the act list.
+#[derive(Debug)]
+pub struct Demo {
+    started: Instant,
+}
+
+impl Default for Demo {
+    fn default() -> Self {
+        Demo {
+            started: Instant::now(),
+        }
+    }
+}
+
+/// Where the demo is in its loop.
+struct Moment {
+    cycle: u64,
+    phase: Phase,
+    /// time since the phase started
+    elapsed: Duration,
+}
+
+impl Moment {
+    fn match_id(&self) -> String {
+        format!("de300000-0000-4000-8000-{:012}", self.cycle)
+    }
+
+    fn map(&self) -> &'static str {
+        MAPS[self.cycle as usize % MAPS.len()]
+    }
+
+    fn in_match(&self) -> bool {
+        matches!(self.phase, Phase::Pregame | Phase::Ingame)
+    }
+
+    /// Rounds won by blue and red. A round is played every few seconds, blue winning the first.
+    fn score(&self) -> (u32, u32) {
+        match self.phase {
+            Phase::Ingame => {
+                let rounds = (self.elapsed.as_secs() / 5) as u32;
+                (rounds.div_ceil(2), rounds / 2)
+            }
+            _ => (0, 0),
+        }
+    }
+
+    /// What player `index` has picked. The user's team hovers and locks one after another, and
+    /// agents move around each loop so every match looks different.
+    fn character(&self, index: usize) -> Character {
+        let agent = AGENTS[(index + self.cycle as usize) % AGENTS.len()].to_string();
+        if self.phase != Phase::Pregame {
+            return Character::Locked(agent);
+        }
+
+        let hover_at = Duration::from_secs(3 + 5 * index as u64);
+        match self.elapsed {
+            t if t < hover_at => Character::None,
+            t if t < hover_at + Duration::from_secs(6) => Character::Hovered(agent),
+            _ => Character::Locked(agent),
+        }
+    }
+}
+
+impl Demo {
+    fn now(&self) -> Moment {
+        let cycle_length: Duration = PHASES.iter().map(|(_, length)| *length).sum();
+        let since_start = self.started.elapsed().as_millis();
+        let cycle = (since_start / cycle_length.as_millis()) as u64 + 1;
+
+        let mut elapsed = Duration::from_millis((since_start % cycle_length.as_millis()) as u64);
+        for (phase, length) in PHASES {
+            if elapsed < length {
+                return Moment {
+                    cycle,
+                    phase,
+                    elapsed,
+                };
+            }
+            elapsed -= length;
+        }
+
+        Moment {
+            cycle,
+            phase: Phase::PostGame,
+            elapsed,
+        }
+    }
+
+    fn puuid(index: usize) -> String {
+        format!("de300000-0000-4000-8000-d{:011}", index)
+    }
+
+    fn presence(index: usize, moment: &Moment) -> presence::Player {
+        let player = &ROSTER[index];
+        let (session_loop_state, party_state) = match moment.phase {
+            Phase::Pregame => ("PREGAME", "DEFAULT"),
+            Phase::Ingame => ("INGAME", "DEFAULT"),
+            Phase::Queue if index != 0 && player.party != ROSTER[0].party => ("MENUS", "DEFAULT"),
+            Phase::Queue => ("MENUS", "MATCHMAKING"),
+            Phase::Menus | Phase::PostGame => ("MENUS", "DEFAULT"),
+        };
+
+        // the score is from the side of the party owner's team
+        let (blue, red) = moment.score();
+        let (ally, enemy) = match index {
+            0..=4 => (blue, red),
+            _ => (red, blue),
+        };
+
+        presence::Player {
+            puuid: Self::puuid(index),
+            game_name: player.name.to_string(),
+            game_tag: player.tag.to_string(),
+            availability: match moment.phase {
+                Phase::Ingame => Availability::Busy,
+                _ => Availability::Online,
+            },
+            private: Private {
+                is_valid: true,
+                match_map: match moment.in_match() {
+                    true => moment.map().to_string(),
+                    false => String::new(),
+                },
+                party_id: player.party.unwrap_or_default().to_string(),
+                player_card_id: String::new(),
+                player_title_id: String::new(),
+                preferred_level_border_id: String::new(),
+                account_level: player.account_level,
+                competitive_tier: player.ranks[0],
+                queue_id: "competitive".to_string(),
+                session_loop_state: session_loop_state.to_string(),
+                party_state: party_state.to_string(),
+                party_size: ROSTER.iter().filter(|p| p.party == player.party).count() as u32,
+                party_owner_match_score_ally_team: ally,
+                party_owner_match_score_enemy_team: enemy,
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl DataSource for Demo {
+    async fn login(&self, _client: &Client) -> Result<Login, LoginFail> {
+        Ok(Login {
+            lockfile: lockfile::Config {
+                name: "Riot Client".to_string(),
+                pid: 0,
+                port: 0,
+                password: String::new(),
+                protocol: "https".to_string(),
+            },
+            entitlements: entitlements::Config {
+                token: String::new(),
+                jwt: String::new(),
+            },
+            session: sessions::Config {
+                puuid: Self::puuid(0),
+                region: Region::Na,
+                shard: Shard::Na,
+                version: "demo".to_string(),
+            },
+            user: Self::presence(0, &self.now()),
+        })
+    }
+
+    async fn presences(
+        &self,
+        _client: &Client,
+        _lockfile: &lockfile::Config,
+    ) -> Result<Vec<presence::Player>> {
+        let moment = self.now();
+
+        Ok(ROSTER
+            .iter()
+            .enumerate()
+            .filter(|(_, player)| player.party.is_some())
+            .map(|(index, _)| Self::presence(index, &moment))
+            .collect())
+    }
+
+    /// Everyone with a presence, and someone who's offline.
+    async fn friends(&self, _client: &Client, _lockfile: &lockfile::Config) -> Result<Vec<Friend>> {
+        let moment = self.now();
+
+        let mut friends = ROSTER
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, player)| player.party.is_some())
+            .map(|(index, player)| {
+                let presence = Self::presence(index, &moment);
+                Friend {
+                    puuid: Self::puuid(index),
+                    game_name: player.name.to_string(),
+                    game_tag: player.tag.to_string(),
+                    availability: presence.availability,
+                    presence: Some(presence),
+                }
+            })
+            .collect::<Vec<_>>();
+        friends.push(Friend {
+            puuid: Self::puuid(ROSTER.len()),
+            game_name: "Phantom".to_string(),
+            game_tag: "afk".to_string(),
+            availability: Availability::Offline,
+            presence: None,
+        });
+
+        Ok(friends)
+    }
+
+    async fn find_match(
+        &self,
+        _client: &Client,
+        _entitlements: &entitlements::Config,
+        _session: &sessions::Config,
+    ) -> Option<String> {
+        let moment = self.now();
+        moment.in_match().then(|| moment.match_id())
+    }
+
+    async fn match_info(
+        &self,
+        _client: &Client,
+        _entitlements: &entitlements::Config,
+        _session: &sessions::Config,
+        match_id: &str,
+        presences: &[presence::Player],
+    ) -> Result<MatchData> {
+        let moment = self.now();
+        if !moment.in_match() || moment.match_id() != match_id {
+            bail!("Demo match {match_id} is over.");
+        }
+
+        // agent select only shows the user's team
+        let visible = match moment.phase {
+            Phase::Pregame => &ROSTER[..5],
+            _ => &ROSTER[..],
+        };
+        let players = visible
+            .iter()
+            .enumerate()
+            .map(|(index, player)| {
+                Player::new(
+                    Self::puuid(index),
+                    match index {
+                        0..=4 => Team::Blue,
+                        _ => Team::Red,
+                    },
+                    moment.character(index),
+                    player.account_level,
+                    player.incognito,
+                    player.hide_account_level,
+                )
+            })
+            .collect();
+
+        let mut info = MatchData::new(
+            moment.phase == Phase::Ingame,
+            moment.map().to_string(),
+            MODE.to_string(),
+            players,
+            Vec::new(),
+        );
+        info.match_id = match_id.to_string();
+        info.assign_parties(presences);
+
+        Ok(info)
+    }
+
+    async fn names(
+        &self,
+        _client: &Client,
+        _entitlements: &entitlements::Config,
+        _session: &sessions::Config,
+        players: &[&Player],
+    ) -> Result<HashMap<String, String>> {
+        Ok(ROSTER
+            .iter()
+            .enumerate()
+            .map(|(index, player)| {
+                (
+                    Self::puuid(index),
+                    format!("{} #{}", player.name, player.tag),
+                )
+            })
+            .filter(|(puuid, _)| players.iter().any(|p| p.puuid == *puuid))
+            .collect())
+    }
+
+    async fn mmr(
+        &self,
+        _client: &Client,
+        _entitlements: &entitlements::Config,
+        _session: &sessions::Config,
+        puuid: &str,
+        acts: &[Season],
+    ) -> Result<History> {
+        let Some(player) = (0..ROSTER.len())
+            .find(|index| Self::puuid(*index) == puuid)
+            .map(|index| &ROSTER[index])
+        else {
+            bail!("{puuid} isn't in the demo lobby.");
+        };
+
+        Ok(acts
+            .iter()
+            .zip(player.ranks)
+            .filter(|(_, tier)| *tier > 0)
+            .map(|(act, tier)| SeasonalInfo {
+                episode_id: act.competitive_tiers_uuid.clone(),
+                season_id: act.season_uuid.clone(),
+                competitive_tier: tier,
+            })
+            .collect())
+    }
+
+    async fn seasons(&self, client: &Client) -> Result<Vec<Season>> {
+        Live.seasons(client).await
+    }
+}
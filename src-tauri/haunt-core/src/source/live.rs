@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use color_eyre::eyre::Result;
+
+use super::DataSource;
+use crate::{
+    local::{
+        entitlements,
+        friends::{self, Friend},
+        presence, sessions,
+    },
+    lockfile,
+    login::{self, Login, LoginFail},
+    pvp::{
+        self,
+        matchdata::{self, MatchData, Player},
+        mmr::{self, History},
+        names,
+    },
+    valapi::seasons::{self, Season},
+    Client,
+};
+
+/// The running Riot client, pd/glz and ValAPI.
+#[derive(Debug, Default)]
+pub struct Live;
+
+#[async_trait]
+impl DataSource for Live {
+    async fn login(&self, client: &Client) -> Result<Login, LoginFail> {
+        login::login(client).await
+    }
+
+    async fn presences(
+        &self,
+        client: &Client,
+        lockfile: &lockfile::Config,
+    ) -> Result<Vec<presence::Player>> {
+        presence::get_presences(lockfile, &client.offline_http).await
+    }
+
+    async fn friends(&self, client: &Client, lockfile: &lockfile::Config) -> Result<Vec<Friend>> {
+        let presences = presence::get_all_presences(lockfile, &client.offline_http).await?;
+        friends::get_friends(lockfile, &client.offline_http, presences).await
+    }
+
+    async fn find_match(
+        &self,
+        client: &Client,
+        entitlements: &entitlements::Config,
+        session: &sessions::Config,
+    ) -> Option<String> {
+        pvp::find_match_id(entitlements, &client.http, &client.rate_limiter, session).await
+    }
+
+    async fn match_info(
+        &self,
+        client: &Client,
+        entitlements: &entitlements::Config,
+        session: &sessions::Config,
+        match_id: &str,
+        presences: &[presence::Player],
+    ) -> Result<MatchData> {
+        matchdata::get_match_info(
+            session,
+            entitlements,
+            match_id,
+            presences,
+            &client.http,
+            &client.rate_limiter,
+        )
+        .await
+    }
+
+    async fn names(
+        &self,
+        client: &Client,
+        entitlements: &entitlements::Config,
+        session: &sessions::Config,
+        players: &[&Player],
+    ) -> Result<HashMap<String, String>> {
+        names::load_player_names(
+            players,
+            session,
+            entitlements,
+            &client.http,
+            &client.rate_limiter,
+            &client.response_cache,
+        )
+        .await
+    }
+
+    async fn mmr(
+        &self,
+        client: &Client,
+        entitlements: &entitlements::Config,
+        session: &sessions::Config,
+        puuid: &str,
+        acts: &[Season],
+    ) -> Result<History> {
+        mmr::get_player_history(
+            puuid,
+            &client.http,
+            &client.rate_limiter,
+            &client.response_cache,
+            entitlements,
+            session,
+            acts,
+        )
+        .await
+    }
+
+    async fn seasons(&self, client: &Client) -> Result<Vec<Season>> {
+        seasons::get_prev_3_seasons(client).await
+    }
+}
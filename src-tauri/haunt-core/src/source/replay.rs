@@ -0,0 +1,130 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use async_trait::async_trait;
+use color_eyre::eyre::Result;
+
+use super::{DataSource, Live};
+use crate::{
+    local::{entitlements, friends::Friend, presence, sessions},
+    lockfile,
+    login::{Login, LoginFail},
+    pvp::{
+        cache::ResponseCache,
+        matchdata::{MatchData, Player},
+        mmr::{self, History},
+        names,
+    },
+    valapi::seasons::Season,
+    Client,
+};
+
+/// A recording made with [`crate::traffic::start_recording`]. The recorded responses go through
+/// the same parsing as live ones, since that's usually what a recording is made to reproduce.
+///
+/// `traffic` answers the requests from the recording process wide. [`super::SourceStore::set`]
+/// starts that as it switches to this source, and stops it when switching away.
+#[derive(Debug)]
+pub struct Replay {
+    recording: PathBuf,
+    /// used instead of the client's cache, which would answer from earlier live lookups and save
+    /// the recorded ones
+    cache: ResponseCache,
+}
+
+impl Replay {
+    pub(super) fn new(recording: &Path) -> Self {
+        Replay {
+            recording: recording.to_path_buf(),
+            cache: ResponseCache::default(),
+        }
+    }
+
+    pub fn recording(&self) -> &Path {
+        &self.recording
+    }
+}
+
+#[async_trait]
+impl DataSource for Replay {
+    async fn login(&self, client: &Client) -> Result<Login, LoginFail> {
+        Live.login(client).await
+    }
+
+    async fn presences(
+        &self,
+        client: &Client,
+        lockfile: &lockfile::Config,
+    ) -> Result<Vec<presence::Player>> {
+        Live.presences(client, lockfile).await
+    }
+
+    async fn friends(&self, client: &Client, lockfile: &lockfile::Config) -> Result<Vec<Friend>> {
+        Live.friends(client, lockfile).await
+    }
+
+    async fn find_match(
+        &self,
+        client: &Client,
+        entitlements: &entitlements::Config,
+        session: &sessions::Config,
+    ) -> Option<String> {
+        Live.find_match(client, entitlements, session).await
+    }
+
+    async fn match_info(
+        &self,
+        client: &Client,
+        entitlements: &entitlements::Config,
+        session: &sessions::Config,
+        match_id: &str,
+        presences: &[presence::Player],
+    ) -> Result<MatchData> {
+        Live.match_info(client, entitlements, session, match_id, presences)
+            .await
+    }
+
+    async fn names(
+        &self,
+        client: &Client,
+        entitlements: &entitlements::Config,
+        session: &sessions::Config,
+        players: &[&Player],
+    ) -> Result<HashMap<String, String>> {
+        names::load_player_names(
+            players,
+            session,
+            entitlements,
+            &client.http,
+            &client.rate_limiter,
+            &self.cache,
+        )
+        .await
+    }
+
+    async fn mmr(
+        &self,
+        client: &Client,
+        entitlements: &entitlements::Config,
+        session: &sessions::Config,
+        puuid: &str,
+        acts: &[Season],
+    ) -> Result<History> {
+        mmr::get_player_history(
+            puuid,
+            &client.http,
+            &client.rate_limiter,
+            &self.cache,
+            entitlements,
+            session,
+            acts,
+        )
+        .await
+    }
+
+    async fn seasons(&self, client: &Client) -> Result<Vec<Season>> {
+        Live.seasons(client).await
+    }
+}
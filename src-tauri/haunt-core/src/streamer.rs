@@ -0,0 +1,175 @@
+//! Streamer mode. Hides who the user is playing with, for when their screen is being broadcast.
+//! Nothing stored is changed, lobbies are masked on their way to the screen.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+
+use crate::lobby::{PlayerUpdate, ShortMatchData};
+
+/// Shown instead of the user's own Riot ID when they hide it too.
+pub const OWN_NAME: &str = "You";
+/// Shown for masked players who haven't been in a lobby this match, so have no alias.
+pub const HIDDEN_NAME: &str = "Hidden player";
+
+/// Why a player's name is hidden.
+enum Masked {
+    /// they're the user, shown as [`OWN_NAME`]
+    User,
+    /// they're outside the user's party, shown by alias
+    Other,
+}
+
+#[derive(Debug, Default)]
+struct MaskState {
+    /// the logged in user's PUUID
+    user: Option<String>,
+    match_id: String,
+    /// the user's party, who keep their names
+    party: HashSet<String>,
+    /// masked name for everyone else, numbered in the order they were first seen
+    aliases: HashMap<String, String>,
+}
+
+impl MaskState {
+    fn alias(&mut self, puuid: &str) -> String {
+        let next = self.aliases.len() + 1;
+        self.aliases
+            .entry(puuid.to_string())
+            .or_insert_with(|| format!("Player {next}"))
+            .clone()
+    }
+
+    /// What `puuid` should be shown as, giving them an alias if they need one. None if their
+    /// name can be shown as-is.
+    fn masked_name(&mut self, puuid: &str, hide_own_name: bool) -> Option<String> {
+        match self.masked(puuid, hide_own_name)? {
+            Masked::User => Some(OWN_NAME.to_string()),
+            Masked::Other => Some(self.alias(puuid)),
+        }
+    }
+
+    /// Why `puuid` is masked. None if they aren't.
+    fn masked(&self, puuid: &str, hide_own_name: bool) -> Option<Masked> {
+        if self.user.as_deref() == Some(puuid) {
+            return hide_own_name.then_some(Masked::User);
+        }
+
+        (!self.party.contains(puuid)).then_some(Masked::Other)
+    }
+}
+
+/// Masks the name and account level of everyone outside the user's party. Each masked player
+/// keeps the same alias until the next match, so they can still be told apart.
+#[derive(Debug, Default)]
+pub struct StreamerMask(Mutex<MaskState>);
+
+impl StreamerMask {
+    /// Sets who the user is. Until it's set everyone is masked, the user included. Aliases are
+    /// kept unless it's a different user.
+    pub fn set_user(&self, puuid: Option<String>) {
+        let mut state = self.0.lock().unwrap();
+        if state.user != puuid {
+            *state = MaskState {
+                user: puuid,
+                ..Default::default()
+            };
+        }
+    }
+
+    /// Masks everyone in the lobby outside the user's party.
+    ///
+    /// * `mask_extra` - Called with the extra data of each masked player, to clear anything in it
+    ///   that could identify them.
+    pub fn lobby<X>(
+        &self,
+        lobby: &mut ShortMatchData<X>,
+        hide_own_name: bool,
+        mut mask_extra: impl FnMut(&mut X),
+    ) {
+        let mut state = self.0.lock().unwrap();
+
+        if state.match_id != lobby.match_id {
+            state.match_id = lobby.match_id.clone();
+            state.aliases.clear();
+        }
+
+        // party IDs only come from presences, so they're known as soon as the lobby is
+        let user = state.user.clone();
+        let party_id = lobby
+            .participants()
+            .find(|p| Some(&p.uuid) == user.as_ref())
+            .map(|p| p.party_id.clone())
+            .filter(|id| !id.is_empty());
+        state.party = lobby
+            .participants()
+            .filter(|p| party_id.is_some() && Some(&p.party_id) == party_id.as_ref())
+            .map(|p| p.uuid.clone())
+            .collect();
+
+        for player in lobby.participants_mut() {
+            let Some(name) = state.masked_name(&player.uuid, hide_own_name) else {
+                continue;
+            };
+
+            player.name = name;
+            player.account_level = None;
+            mask_extra(&mut player.extra);
+        }
+    }
+
+    /// Masks a player's name as it finishes loading. Players that haven't been seen in a lobby
+    /// yet are masked too, since there's no telling whether they're in the user's party.
+    pub fn update(&self, update: &mut PlayerUpdate, hide_own_name: bool) {
+        if update.name.is_none() {
+            return;
+        }
+
+        if let Some(name) = self
+            .0
+            .lock()
+            .unwrap()
+            .masked_name(&update.uuid, hide_own_name)
+        {
+            update.name = Some(name);
+        }
+    }
+
+    /// What the player should be shown as, e.g. in alerts and history. None if their name can be
+    /// shown as-is. Unlike lobbies this never hands out an alias, so looking players up doesn't
+    /// change how the next lobby is numbered. Masked players without one are [`HIDDEN_NAME`].
+    pub fn name(&self, puuid: &str, hide_own_name: bool) -> Option<String> {
+        let state = self.0.lock().unwrap();
+        match state.masked(puuid, hide_own_name)? {
+            Masked::User => Some(OWN_NAME.to_string()),
+            Masked::Other => Some(
+                state
+                    .aliases
+                    .get(puuid)
+                    .cloned()
+                    .unwrap_or_else(|| HIDDEN_NAME.to_string()),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looking_up_names_hands_out_no_aliases() {
+        let mask = StreamerMask::default();
+        mask.set_user(Some("user".to_string()));
+
+        // e.g. a friend or the history of someone from an earlier match
+        assert_eq!(mask.name("friend", false).as_deref(), Some(HIDDEN_NAME));
+        assert_eq!(mask.name("user", false), None);
+        assert_eq!(mask.name("user", true).as_deref(), Some(OWN_NAME));
+
+        let lobby = ["enemy", "friend"].map(|p| mask.0.lock().unwrap().masked_name(p, false));
+        assert_eq!(lobby.map(Option::unwrap), ["Player 1", "Player 2"]);
+        assert_eq!(mask.name("friend", false).as_deref(), Some("Player 2"));
+    }
+}
@@ -0,0 +1,751 @@
+//! Opt-in recording of every local client and pd/glz request, for bug reports. Recordings are
+//! redacted as they're written: tokens and passwords are dropped, and PUUIDs and names are swapped
+//! for stand-ins that stay consistent across the session. Replaying a recording answers those
+//! requests from it instead of the network, so a report can be reproduced without the game.
+//!
+//! Set `HAUNT_RECORD` to a directory and call [`from_env`], or use [`start_recording`] directly.
+//! Replays are started by switching to [`crate::source::Backend::Replay`]. Both apply to every
+//! [`crate::Client`] in the process. ValAPI requests are public, so they're never recorded.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::{self, File},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use base64::prelude::*;
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::{bail, eyre, Result};
+use reqwest::{
+    header::{HeaderValue, CONTENT_TYPE},
+    Request, RequestBuilder, Response, StatusCode, Url,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Bumped whenever [`Exchange`] changes in a way old recordings can't be read with.
+const FORMAT: u32 = 1;
+
+const REDACTED: &str = "<redacted>";
+
+/// Keys holding tokens, wherever they show up.
+const SECRET_KEYS: [&str; 8] = [
+    "accessToken",
+    "access_token",
+    "token",
+    "idToken",
+    "id_token",
+    "refreshToken",
+    "privateJwt",
+    "password",
+];
+/// Keys holding PUUIDs. Other UUIDs, like agents and seasons, are left alone.
+const PUUID_KEYS: [&str; 4] = ["puuid", "Subject", "subject", "ObserverSubjects"];
+const NAME_KEYS: [&str; 6] = [
+    "game_name",
+    "gameName",
+    "GameName",
+    "name",
+    "DisplayName",
+    "displayName",
+];
+const TAG_KEYS: [&str; 3] = ["game_tag", "tagLine", "TagLine"];
+
+static MODE: Mutex<Mode> = Mutex::new(Mode::Off);
+
+enum Mode {
+    Off,
+    Recording(Recorder),
+    Replaying(Replay),
+}
+
+/// The first line of a recording.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Header {
+    format: u32,
+    haunt_version: String,
+    recorded_at: DateTime<Utc>,
+}
+
+/// A request and the response it got. Every line after the header is one of these.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Exchange {
+    method: String,
+    /// path and query. hosts are left out, since the local client's port changes every launch
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_body: Option<String>,
+    status: u16,
+    response_body: String,
+    at: DateTime<Utc>,
+}
+
+/// What a request is matched on when replaying.
+type Key = (String, String, Option<String>);
+
+/// Array bodies, like the name service's list of PUUIDs, are sorted, since the order they're sent
+/// in can change from one run to the next.
+fn key(method: &str, path: &str, body: Option<&str>) -> Key {
+    let body = body.map(|body| match serde_json::from_str::<Value>(body) {
+        Ok(Value::Array(mut values)) => {
+            values.sort_by_key(Value::to_string);
+            Value::Array(values).to_string()
+        }
+        _ => body.to_string(),
+    });
+
+    (method.to_string(), path.to_string(), body)
+}
+
+impl Exchange {
+    fn key(&self) -> Key {
+        key(&self.method, &self.path, self.request_body.as_deref())
+    }
+}
+
+struct Recorder {
+    path: PathBuf,
+    file: File,
+    redactor: Redactor,
+}
+
+/// Responses to one request, in the order they were recorded. The last one is repeated once the
+/// rest have been served, so polling carries on from where the recording ended.
+#[derive(Default)]
+struct Responses {
+    queue: VecDeque<Exchange>,
+    last: Option<Exchange>,
+}
+
+/// A recording loaded for replaying. See [`start_replay`].
+pub(crate) struct Replay {
+    responses: HashMap<Key, Responses>,
+}
+
+/// Swaps PUUIDs, names and secrets for stand-ins. The same real value always gets the same
+/// stand-in, so players can still be followed from one request to the next.
+#[derive(Default)]
+struct Redactor {
+    puuids: HashMap<String, String>,
+    names: HashMap<String, String>,
+    tags: HashMap<String, String>,
+}
+
+fn is_uuid(s: &str) -> bool {
+    s.len() == 36
+        && s.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+fn alias(
+    map: &mut HashMap<String, String>,
+    real: &str,
+    make: impl FnOnce(usize) -> String,
+) -> String {
+    let next = map.len() + 1;
+    map.entry(real.to_string())
+        .or_insert_with(|| make(next))
+        .clone()
+}
+
+impl Redactor {
+    fn puuid(&mut self, real: &str) -> String {
+        alias(&mut self.puuids, real, |n| {
+            format!("00000000-0000-4000-8000-{n:012}")
+        })
+    }
+
+    /// Learns the PUUIDs in `value`, so they can be replaced wherever else they turn up.
+    fn collect(&mut self, value: &Value, key: Option<&str>) {
+        match value {
+            Value::String(s) if key.is_some_and(|k| PUUID_KEYS.contains(&k)) && is_uuid(s) => {
+                self.puuid(s);
+            }
+            // launch arguments
+            Value::String(s) => {
+                if let Some(puuid) = s.strip_prefix("-subject=").filter(|p| is_uuid(p)) {
+                    self.puuid(puuid);
+                }
+            }
+            Value::Array(values) => values.iter().for_each(|v| self.collect(v, key)),
+            Value::Object(map) => map.iter().for_each(|(k, v)| self.collect(v, Some(k))),
+            _ => (),
+        }
+    }
+
+    fn replace_puuids(&self, s: &str) -> String {
+        self.puuids
+            .iter()
+            .fold(s.to_string(), |s, (real, alias)| s.replace(real, alias))
+    }
+
+    fn redact(&mut self, value: &mut Value, key: Option<&str>) {
+        let key = key.unwrap_or_default();
+
+        match value {
+            Value::String(s) if SECRET_KEYS.contains(&key) => *s = REDACTED.to_string(),
+            // what the user wrote about each friend
+            Value::String(s) if key == "note" && !s.is_empty() => *s = REDACTED.to_string(),
+            Value::String(s) if NAME_KEYS.contains(&key) && !s.is_empty() => {
+                *s = alias(&mut self.names, s, |n| format!("Player{n}"));
+            }
+            Value::String(s) if TAG_KEYS.contains(&key) && !s.is_empty() => {
+                *s = alias(&mut self.tags, s, |n| format!("{n:04}"));
+            }
+            // private presences are base64 encoded json
+            Value::String(s) if key == "private" => match self.redact_base64(s) {
+                Some(redacted) => *s = redacted,
+                None => *s = self.replace_puuids(s),
+            },
+            // launch arguments carry the client's auth tokens
+            Value::String(s) if s.starts_with('-') && s.contains("token") => {
+                let name = s.split('=').next().unwrap_or_default();
+                *s = format!("{name}={REDACTED}");
+            }
+            Value::String(s) => *s = self.replace_puuids(s),
+            Value::Array(values) => values.iter_mut().for_each(|v| self.redact(v, Some(key))),
+            Value::Object(map) => {
+                // keys can be PUUIDs too, e.g. in match details
+                let redacted = std::mem::take(map)
+                    .into_iter()
+                    .map(|(k, mut v)| {
+                        self.redact(&mut v, Some(&k));
+                        (self.replace_puuids(&k), v)
+                    })
+                    .collect();
+                *map = redacted;
+            }
+            _ => (),
+        }
+    }
+
+    fn redact_base64(&mut self, s: &str) -> Option<String> {
+        let decoded = BASE64_STANDARD.decode(s).ok()?;
+        let mut value = serde_json::from_slice::<Value>(&decoded).ok()?;
+        self.collect(&value, None);
+        self.redact(&mut value, None);
+        Some(BASE64_STANDARD.encode(value.to_string()))
+    }
+
+    /// Redacts a body, whether or not it's json.
+    fn redact_body(&mut self, body: &str) -> String {
+        match serde_json::from_str::<Value>(body) {
+            Ok(mut value) => {
+                self.redact(&mut value, None);
+                value.to_string()
+            }
+            Err(_) => self.replace_puuids(body),
+        }
+    }
+
+    fn exchange(
+        &mut self,
+        method: &str,
+        url: &Url,
+        request_body: Option<&str>,
+        status: StatusCode,
+        response_body: &str,
+    ) -> Exchange {
+        let path = match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_string(),
+        };
+
+        // PUUIDs in paths always follow `players`, e.g. /mmr/v1/players/{puuid}
+        let segments = path.split(['/', '?']).collect::<Vec<_>>();
+        for pair in segments.windows(2) {
+            if pair[0] == "players" && is_uuid(pair[1]) {
+                self.puuid(pair[1]);
+            }
+        }
+        // the name service takes a list of bare PUUIDs
+        let request = request_body.and_then(|b| serde_json::from_str::<Value>(b).ok());
+        if let Some(Value::Array(values)) = &request {
+            for puuid in values
+                .iter()
+                .filter_map(Value::as_str)
+                .filter(|p| is_uuid(p))
+            {
+                self.puuid(puuid);
+            }
+        }
+        let response = serde_json::from_str::<Value>(response_body).ok();
+        if let Some(response) = &response {
+            self.collect(response, None);
+        }
+
+        Exchange {
+            method: method.to_string(),
+            path: self.replace_puuids(&path),
+            request_body: request_body.map(|b| self.redact_body(b)),
+            status: status.as_u16(),
+            response_body: self.redact_body(response_body),
+            at: Utc::now(),
+        }
+    }
+}
+
+impl Recorder {
+    fn create(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+
+        let recorded_at = Utc::now();
+        let path = dir.join(format!(
+            "haunt-{}.jsonl",
+            recorded_at.format("%Y%m%d-%H%M%S")
+        ));
+        let mut file = File::create(&path)?;
+
+        let header = Header {
+            format: FORMAT,
+            haunt_version: env!("CARGO_PKG_VERSION").to_string(),
+            recorded_at,
+        };
+        writeln!(file, "{}", serde_json::to_string(&header)?)?;
+
+        Ok(Recorder {
+            path,
+            file,
+            redactor: Redactor::default(),
+        })
+    }
+
+    // written as it goes, so a recording survives whatever crash it was made to capture
+    fn write(&mut self, exchange: &Exchange) -> Result<()> {
+        writeln!(self.file, "{}", serde_json::to_string(exchange)?)?;
+        Ok(())
+    }
+}
+
+impl Replay {
+    /// Reads a recording, without replaying it yet.
+    pub(crate) fn load(archive: &Path) -> Result<Self> {
+        let mut lines = BufReader::new(File::open(archive)?).lines();
+
+        let header = lines
+            .next()
+            .ok_or_else(|| eyre!("The recording is empty."))??;
+        let header: Header = serde_json::from_str(&header)?;
+        if header.format != FORMAT {
+            bail!(
+                "The recording is format {}, but this version of Haunt reads format {FORMAT}.",
+                header.format
+            );
+        }
+
+        let mut responses = HashMap::<Key, Responses>::new();
+        for line in lines {
+            let exchange: Exchange = serde_json::from_str(&line?)?;
+            responses
+                .entry(exchange.key())
+                .or_default()
+                .queue
+                .push_back(exchange);
+        }
+
+        info!(
+            "Replaying {} requests recorded by Haunt {} at {}.",
+            responses.len(),
+            header.haunt_version,
+            header.recorded_at
+        );
+        Ok(Replay { responses })
+    }
+
+    fn next(&mut self, key: &Key) -> Option<Exchange> {
+        let responses = self.responses.get_mut(key)?;
+        if let Some(next) = responses.queue.pop_front() {
+            responses.last = Some(next);
+        }
+
+        responses.last.clone()
+    }
+}
+
+/// Starts recording to a new file in `dir`, ending any recording already running. Fails while a
+/// replay is running, since replayed requests never reach the network.
+/// Returns the file's path.
+pub fn start_recording(dir: &Path) -> Result<PathBuf> {
+    let mut mode = MODE.lock().unwrap();
+    if matches!(*mode, Mode::Replaying(_)) {
+        bail!("Switch back to the game before recording.");
+    }
+
+    let recorder = Recorder::create(dir)?;
+    let path = recorder.path.clone();
+
+    *mode = Mode::Recording(recorder);
+    info!("Recording local and pd/glz traffic to {}.", path.display());
+
+    Ok(path)
+}
+
+/// Stops recording. Returns the recording's path, or None if nothing was being recorded.
+pub fn stop_recording() -> Option<PathBuf> {
+    let mut mode = MODE.lock().unwrap();
+    let Mode::Recording(recorder) = &*mode else {
+        return None;
+    };
+
+    let path = recorder.path.clone();
+    *mode = Mode::Off;
+    info!("Stopped recording to {}.", path.display());
+
+    Some(path)
+}
+
+/// Answers local and pd/glz requests from a recording instead of the network, replacing any
+/// replay already running. Fails while recording, rather than ending the recording.
+pub(crate) fn start_replay(replay: Replay) -> Result<()> {
+    let mut mode = MODE.lock().unwrap();
+    if matches!(*mode, Mode::Recording(_)) {
+        bail!("Stop recording before replaying.");
+    }
+
+    *mode = Mode::Replaying(replay);
+
+    Ok(())
+}
+
+/// Goes back to the network. Does nothing if no replay is running.
+pub fn stop_replay() {
+    let mut mode = MODE.lock().unwrap();
+    if matches!(*mode, Mode::Replaying(_)) {
+        *mode = Mode::Off;
+    }
+}
+
+pub fn is_replaying() -> bool {
+    matches!(*MODE.lock().unwrap(), Mode::Replaying(_))
+}
+
+pub fn is_recording() -> bool {
+    matches!(*MODE.lock().unwrap(), Mode::Recording(_))
+}
+
+/// Starts recording if `HAUNT_RECORD` is set.
+pub fn from_env() -> Result<()> {
+    match std::env::var_os("HAUNT_RECORD") {
+        Some(dir) if !dir.is_empty() => start_recording(Path::new(&dir)).map(|_| ()),
+        _ => Ok(()),
+    }
+}
+
+fn replayed_response(exchange: Option<Exchange>) -> Response {
+    let (status, body) = match exchange {
+        Some(exchange) => (
+            StatusCode::from_u16(exchange.status).unwrap_or(StatusCode::OK),
+            exchange.response_body,
+        ),
+        None => (StatusCode::NOT_FOUND, String::new()),
+    };
+
+    let mut res = http::Response::new(body.into_bytes());
+    *res.status_mut() = status;
+    res.headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+
+    Response::from(res)
+}
+
+/// Sends a local or pd/glz request, recording it or answering it from a replay if either is
+/// running.
+pub(crate) async fn execute(
+    client: &reqwest::Client,
+    request: Request,
+) -> reqwest::Result<Response> {
+    let method = request.method().to_string();
+    let url = request.url().clone();
+    let request_body = request
+        .body()
+        .and_then(|b| b.as_bytes())
+        .map(|b| String::from_utf8_lossy(b).into_owned());
+
+    let recording = {
+        let mut mode = MODE.lock().unwrap();
+        match &mut *mode {
+            Mode::Off => false,
+            Mode::Recording(_) => true,
+            Mode::Replaying(replay) => {
+                // recordings are keyed on redacted requests, which is what replayed clients send
+                let path = match url.query() {
+                    Some(query) => format!("{}?{query}", url.path()),
+                    None => url.path().to_string(),
+                };
+                let exchange = replay.next(&key(&method, &path, request_body.as_deref()));
+                if exchange.is_none() {
+                    warn!("Nothing recorded for {url}.");
+                }
+
+                return Ok(replayed_response(exchange));
+            }
+        }
+    };
+
+    let res = client.execute(request).await?;
+    if !recording {
+        return Ok(res);
+    }
+
+    // the body can only be read once, so the caller gets a copy
+    let status = res.status();
+    let headers = res.headers().clone();
+    let body = res.bytes().await?;
+
+    if let Mode::Recording(recorder) = &mut *MODE.lock().unwrap() {
+        let exchange = recorder.redactor.exchange(
+            &method,
+            &url,
+            request_body.as_deref(),
+            status,
+            &String::from_utf8_lossy(&body),
+        );
+        if let Err(why) = recorder.write(&exchange) {
+            warn!("Unable to record {url}: {why}");
+        }
+    }
+
+    let mut copy = http::Response::new(body.to_vec());
+    *copy.status_mut() = status;
+    *copy.headers_mut() = headers;
+
+    Ok(Response::from(copy))
+}
+
+/// [`execute`] for a request that hasn't been built yet.
+pub(crate) async fn send(request: RequestBuilder) -> reqwest::Result<Response> {
+    let (client, request) = request.build_split();
+    execute(&client, request?).await
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    const USER: &str = "11111111-2222-4333-8444-555555555555";
+    const OTHER: &str = "66666666-7777-4888-9999-aaaaaaaaaaaa";
+    const ACCESS_TOKEN: &str = "eyJhbGciOiJSUzI1NiJ9.access";
+    const ENTITLEMENTS_TOKEN: &str = "eyJraWQiOiJrMSJ9.entitlements";
+
+    fn exchange(
+        redactor: &mut Redactor,
+        path: &str,
+        request_body: Option<&Value>,
+        response_body: &Value,
+    ) -> Exchange {
+        let url = Url::parse(&format!("https://127.0.0.1:54321{path}")).unwrap();
+        redactor.exchange(
+            "GET",
+            &url,
+            request_body.map(Value::to_string).as_deref(),
+            StatusCode::OK,
+            &response_body.to_string(),
+        )
+    }
+
+    /// Fails if any of `secrets` made it into the recorded exchange.
+    fn assert_redacted(exchange: &Exchange, secrets: &[&str]) {
+        let recorded = serde_json::to_string(exchange).unwrap();
+        for secret in secrets {
+            assert!(!recorded.contains(secret), "{secret} survived: {recorded}");
+        }
+    }
+
+    fn response(exchange: &Exchange) -> Value {
+        serde_json::from_str(&exchange.response_body).unwrap()
+    }
+
+    fn entitlements(redactor: &mut Redactor) -> Exchange {
+        exchange(
+            redactor,
+            "/entitlements/v1/token",
+            None,
+            &json!({
+                "accessToken": ACCESS_TOKEN,
+                "entitlements": [],
+                "issuer": "https://entitlements.auth.riotgames.com",
+                "subject": USER,
+                "token": ENTITLEMENTS_TOKEN,
+            }),
+        )
+    }
+
+    #[test]
+    fn entitlements_lose_their_tokens() {
+        let mut redactor = Redactor::default();
+        let exchange = entitlements(&mut redactor);
+
+        assert_redacted(&exchange, &[ACCESS_TOKEN, ENTITLEMENTS_TOKEN, USER]);
+        assert_eq!(response(&exchange)["subject"], redactor.puuid(USER));
+    }
+
+    #[test]
+    fn launch_arguments_lose_their_tokens() {
+        let mut redactor = Redactor::default();
+        let exchange = exchange(
+            &mut redactor,
+            "/product-session/v1/external-sessions",
+            None,
+            &json!({
+                "host_app": {
+                    "launchConfiguration": {
+                        "arguments": [
+                            "-ares-deployment=eu",
+                            format!("-login-token={ACCESS_TOKEN}"),
+                            format!("-subject={USER}"),
+                        ],
+                    },
+                    "productId": "valorant",
+                    "version": "release-07.12-shipping-9-2207919",
+                },
+            }),
+        );
+
+        assert_redacted(&exchange, &[ACCESS_TOKEN, USER]);
+        let arguments = &response(&exchange)["host_app"]["launchConfiguration"]["arguments"];
+        assert_eq!(arguments[0], "-ares-deployment=eu");
+        assert_eq!(arguments[1], format!("-login-token={REDACTED}"));
+        assert_eq!(arguments[2], format!("-subject={}", redactor.puuid(USER)));
+    }
+
+    #[test]
+    fn private_presences_are_redacted_inside() {
+        let private = json!({
+            "isValid": true,
+            "partyId": "3b8ab5c8-c1b0-4f2e-9b02-6dd61e57fb10",
+            "partyOwnerPuuid": OTHER,
+            "competitiveTier": 12,
+        });
+        let mut redactor = Redactor::default();
+        let exchange = exchange(
+            &mut redactor,
+            "/chat/v4/presences",
+            None,
+            &json!({
+                "presences": [
+                    {
+                        "puuid": USER,
+                        "game_name": "RealName",
+                        "game_tag": "EUW",
+                        "private": BASE64_STANDARD.encode(private.to_string()),
+                    },
+                    { "puuid": OTHER, "game_name": "Friend", "game_tag": "1234" },
+                ],
+            }),
+        );
+
+        assert_redacted(&exchange, &[USER, OTHER, "RealName", "Friend"]);
+        let presence = &response(&exchange)["presences"][0];
+        assert_eq!(presence["game_name"], "Player1");
+        assert_eq!(presence["game_tag"], "0001");
+
+        let private = BASE64_STANDARD
+            .decode(presence["private"].as_str().unwrap())
+            .unwrap();
+        let private = serde_json::from_slice::<Value>(&private).unwrap();
+        assert_eq!(private["partyOwnerPuuid"], redactor.puuid(OTHER));
+        // only PUUIDs are swapped, other ids are left alone
+        assert_eq!(private["partyId"], "3b8ab5c8-c1b0-4f2e-9b02-6dd61e57fb10");
+        assert_eq!(private["competitiveTier"], 12);
+    }
+
+    #[test]
+    fn name_service_requests_and_responses_are_redacted() {
+        let mut redactor = Redactor::default();
+        let exchange = exchange(
+            &mut redactor,
+            "/name-service/v2/players",
+            Some(&json!([USER, OTHER])),
+            &json!([
+                { "DisplayName": "", "Subject": USER, "GameName": "RealName", "TagLine": "EUW" },
+                { "DisplayName": "", "Subject": OTHER, "GameName": "Friend", "TagLine": "1234" },
+            ]),
+        );
+
+        assert_redacted(&exchange, &[USER, OTHER, "RealName", "Friend"]);
+        let request = serde_json::from_str::<Value>(exchange.request_body.as_ref().unwrap());
+        assert_eq!(
+            request.unwrap(),
+            json!([redactor.puuid(USER), redactor.puuid(OTHER)])
+        );
+    }
+
+    #[test]
+    fn match_details_are_redacted_keys_and_all() {
+        let mut redactor = Redactor::default();
+        let exchange = exchange(
+            &mut redactor,
+            "/match-details/v1/matches/0b3d1c9e-1c5a-4a5f-bf4e-7f3d2b1a9c8d",
+            None,
+            &json!({
+                "matchInfo": { "mapId": "/Game/Maps/Ascent/Ascent" },
+                "players": [
+                    { "subject": USER, "gameName": "RealName", "tagLine": "EUW", "teamId": "Blue" },
+                    { "subject": OTHER, "gameName": "Friend", "tagLine": "1234", "teamId": "Red" },
+                ],
+                "kills": [{ "killer": USER, "victim": OTHER }],
+                "roundResults": [{ "playerEconomies": { USER: { "loadoutValue": 3900 } } }],
+            }),
+        );
+
+        assert_redacted(&exchange, &[USER, OTHER, "RealName", "Friend"]);
+        let details = response(&exchange);
+        assert_eq!(details["kills"][0]["victim"], redactor.puuid(OTHER));
+        assert!(details["roundResults"][0]["playerEconomies"]
+            .get(redactor.puuid(USER))
+            .is_some());
+    }
+
+    #[test]
+    fn aliases_stay_the_same_across_requests() {
+        let mut redactor = Redactor::default();
+        let entitlements = response(&entitlements(&mut redactor));
+        let names = exchange(
+            &mut redactor,
+            "/name-service/v2/players",
+            Some(&json!([OTHER, USER])),
+            &json!([
+                { "Subject": OTHER, "GameName": "Friend", "TagLine": "1234" },
+                { "Subject": USER, "GameName": "RealName", "TagLine": "EUW" },
+            ]),
+        );
+        let presences = exchange(
+            &mut redactor,
+            "/chat/v4/presences",
+            None,
+            &json!({ "presences": [{ "puuid": USER, "game_name": "RealName", "game_tag": "EUW" }] }),
+        );
+        let mmr = exchange(
+            &mut redactor,
+            &format!("/mmr/v1/players/{USER}"),
+            None,
+            &json!({ "Subject": USER }),
+        );
+
+        let user = entitlements["subject"].as_str().unwrap().to_string();
+        let names = response(&names);
+        let presence = &response(&presences)["presences"][0];
+        assert_eq!(names[1]["Subject"], user);
+        assert_eq!(presence["puuid"], user);
+        assert_eq!(mmr.path, format!("/mmr/v1/players/{user}"));
+        assert_eq!(presence["game_name"], names[1]["GameName"]);
+        assert_eq!(presence["game_tag"], names[1]["TagLine"]);
+    }
+
+    #[test]
+    fn array_bodies_match_in_any_order() {
+        let names =
+            |puuids: Value| key("PUT", "/name-service/v2/players", Some(&puuids.to_string()));
+
+        assert_eq!(names(json!([USER, OTHER])), names(json!([OTHER, USER])));
+        assert_ne!(names(json!([USER])), names(json!([OTHER])));
+    }
+}
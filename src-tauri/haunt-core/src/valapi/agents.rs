@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Agent {
+    pub uuid: String,
+    pub display_name: String,
+    pub display_icon: Option<String>,
+}
+
+impl Agent {
+    /// Stand-in for an agent that isn't in the loaded content, e.g. before ValAPI content has
+    /// loaded. Shows the UUID as its name.
+    pub fn unknown(uuid: &str) -> Self {
+        Agent {
+            uuid: uuid.to_string(),
+            display_name: uuid.to_string(),
+            display_icon: None,
+        }
+    }
+}
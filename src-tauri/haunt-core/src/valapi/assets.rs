@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+// these are only the fields Haunt cares about. ValAPI returns a lot more for most of them.
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Map {
+    pub uuid: String,
+    pub display_name: String,
+    /// the path the game uses for the map, e.g. `/Game/Maps/Ascent/Ascent`
+    pub map_url: String,
+    pub list_view_icon: Option<String>,
+    pub splash: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameMode {
+    pub uuid: String,
+    pub display_name: String,
+    pub asset_path: String,
+    pub display_icon: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerCard {
+    pub uuid: String,
+    pub display_name: String,
+    pub small_art: Option<String>,
+    pub wide_art: Option<String>,
+    pub large_art: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerTitle {
+    pub uuid: String,
+    pub display_name: Option<String>,
+    pub title_text: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkinChroma {
+    pub uuid: String,
+    pub display_name: String,
+    pub display_icon: Option<String>,
+    pub full_render: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Spray {
+    pub uuid: String,
+    pub display_name: String,
+    pub display_icon: Option<String>,
+    pub full_transparent_icon: Option<String>,
+}
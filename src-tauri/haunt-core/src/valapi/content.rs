@@ -0,0 +1,208 @@
+use std::{
+    fs,
+    path::Path,
+    sync::{Arc, RwLock},
+};
+
+use color_eyre::eyre::Result;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::Client;
+
+use super::{
+    agents::Agent,
+    assets::{GameMode, Map, PlayerCard, PlayerTitle, SkinChroma, Spray},
+    seasons::{flatten_tiers, CompetitiveTier, CompetitiveTierResponseData, Season},
+    urls::{self, Url},
+};
+
+#[derive(Deserialize)]
+struct ValApiResponse<T> {
+    data: T,
+}
+
+/// The ValAPI version the content was fetched for. The cache is refreshed whenever this changes.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Version {
+    pub manifest_id: String,
+    pub version: String,
+    /// the language names were fetched in. not part of ValAPI's response, but changing it means
+    /// refetching just like a new version does
+    #[serde(default)]
+    pub language: String,
+}
+
+/// Everything Haunt needs from ValAPI, loaded once and shared through [`ContentStore`].
+#[derive(Clone, Debug, Default)]
+pub struct Content {
+    pub version: Version,
+    pub agents: Vec<Agent>,
+    pub competitive_tiers: Vec<CompetitiveTier>,
+    /// every competitive season, acts and episodes included. see `seasons::get_prev_3_seasons`
+    pub seasons: Vec<Season>,
+    pub maps: Vec<Map>,
+    pub game_modes: Vec<GameMode>,
+    pub cards: Vec<PlayerCard>,
+    pub titles: Vec<PlayerTitle>,
+    pub skins: Vec<SkinChroma>,
+    pub sprays: Vec<Spray>,
+}
+
+impl Content {
+    /// Whether the content is missing anything match loading depends on.
+    pub fn is_empty(&self) -> bool {
+        self.agents.is_empty() || self.competitive_tiers.is_empty()
+    }
+}
+
+/// Holds the current [`Content`]. Readers get a cheap snapshot, so nothing holds a lock across
+/// an await point while the background refresh swaps in new content.
+#[derive(Debug, Default)]
+pub struct ContentStore(RwLock<Arc<Content>>);
+
+impl ContentStore {
+    /// Returns a snapshot of the current content.
+    pub fn get(&self) -> Arc<Content> {
+        self.0.read().unwrap().clone()
+    }
+
+    pub fn set(&self, content: Content) {
+        *self.0.write().unwrap() = Arc::new(content);
+    }
+}
+
+fn read<T: DeserializeOwned>(dir: &Path, url: &Url) -> Result<T> {
+    let file = fs::read_to_string(dir.join(url.suffix))?;
+    Ok(serde_json::from_str(&file)?)
+}
+
+fn write<T: Serialize>(dir: &Path, url: &Url, value: &T) -> Result<()> {
+    fs::write(dir.join(url.suffix), serde_json::to_string(value)?)?;
+    Ok(())
+}
+
+/// Loads the content cache written by a previous run.
+///
+/// * `dir` - The directory the cache was written to.
+pub fn load_from_disk(dir: &Path) -> Result<Content> {
+    let content = Content {
+        version: read(dir, &urls::VERSION)?,
+        agents: read(dir, &urls::AGENTS)?,
+        competitive_tiers: read(dir, &urls::RANKS)?,
+        seasons: read(dir, &urls::SEASONS)?,
+        maps: read(dir, &urls::MAPS)?,
+        game_modes: read(dir, &urls::GAMEMODES)?,
+        cards: read(dir, &urls::CARDS)?,
+        titles: read(dir, &urls::TITLES)?,
+        skins: read(dir, &urls::SKINS)?,
+        sprays: read(dir, &urls::SPRAYS)?,
+    };
+
+    info!(
+        "Loaded ValAPI content {} from disk.",
+        content.version.version
+    );
+
+    Ok(content)
+}
+
+fn save_to_disk(dir: &Path, content: &Content) -> Result<()> {
+    fs::create_dir_all(dir)?;
+
+    write(dir, &urls::AGENTS, &content.agents)?;
+    write(dir, &urls::RANKS, &content.competitive_tiers)?;
+    write(dir, &urls::SEASONS, &content.seasons)?;
+    write(dir, &urls::MAPS, &content.maps)?;
+    write(dir, &urls::GAMEMODES, &content.game_modes)?;
+    write(dir, &urls::CARDS, &content.cards)?;
+    write(dir, &urls::TITLES, &content.titles)?;
+    write(dir, &urls::SKINS, &content.skins)?;
+    write(dir, &urls::SPRAYS, &content.sprays)?;
+    // written last so a half-written cache is never mistaken for an up to date one
+    write(dir, &urls::VERSION, &content.version)?;
+
+    Ok(())
+}
+
+async fn fetch<T: DeserializeOwned>(
+    http: &reqwest::Client,
+    url: &Url,
+    language: &str,
+) -> Result<T> {
+    let res = http
+        .get(format!("{}{}", super::base_url(), url.url))
+        .query(&[("language", language)])
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<ValApiResponse<T>>()
+        .await?;
+
+    debug!("Fetched {} from ValAPI.", url.name);
+
+    Ok(res.data)
+}
+
+/// Fetches every piece of content for the given version.
+async fn fetch_content(http: &reqwest::Client, version: Version) -> Result<Content> {
+    let language = version.language.as_str();
+    let (agents, tiers, seasons, maps, game_modes, cards, titles, skins, sprays) = futures::try_join!(
+        fetch::<Vec<Agent>>(http, &urls::AGENTS, language),
+        fetch::<Vec<CompetitiveTierResponseData>>(http, &urls::RANKS, language),
+        fetch::<Vec<Season>>(http, &urls::SEASONS, language),
+        fetch::<Vec<Map>>(http, &urls::MAPS, language),
+        fetch::<Vec<GameMode>>(http, &urls::GAMEMODES, language),
+        fetch::<Vec<PlayerCard>>(http, &urls::CARDS, language),
+        fetch::<Vec<PlayerTitle>>(http, &urls::TITLES, language),
+        fetch::<Vec<SkinChroma>>(http, &urls::SKINS, language),
+        fetch::<Vec<Spray>>(http, &urls::SPRAYS, language),
+    )?;
+
+    Ok(Content {
+        version,
+        agents,
+        competitive_tiers: flatten_tiers(tiers),
+        seasons,
+        maps,
+        game_modes,
+        cards,
+        titles,
+        skins,
+        sprays,
+    })
+}
+
+impl Client {
+    /// Refetches all content if the ValAPI version or the language setting changed since it was
+    /// last loaded, then swaps it in. Returns the current version.
+    ///
+    /// * `cache_dir` - Where to write the new content, so [`load_from_disk`] can pick it up next
+    ///   time. Nothing is written if None.
+    pub async fn refresh_content(&self, cache_dir: Option<&Path>) -> Result<Version> {
+        let language = self.settings.get().language.clone();
+        let version = Version {
+            language: language.clone(),
+            ..fetch::<Version>(&self.http, &urls::VERSION, &language).await?
+        };
+        let current = self.content.get();
+        if current.version == version && !current.is_empty() {
+            info!("ValAPI content is up to date ({}).", version.version);
+            return Ok(version);
+        }
+
+        info!("Updating ValAPI content to {}...", version.version);
+        let content = fetch_content(&self.http, version.clone()).await?;
+
+        if let Some(dir) = cache_dir {
+            if let Err(why) = save_to_disk(dir, &content) {
+                warn!("Unable to write ValAPI content to disk: {why}");
+            }
+        }
+
+        self.content.set(content);
+        info!("ValAPI content updated.");
+
+        Ok(version)
+    }
+}
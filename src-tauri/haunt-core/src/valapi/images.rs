@@ -0,0 +1,210 @@
+use std::{fs, path::Path, sync::Mutex, time::SystemTime};
+
+use color_eyre::eyre::{bail, Result};
+
+/// Scheme of the custom protocol ValAPI images are served through.
+pub const PROTOCOL: &str = "haunt-asset";
+
+/// Where ValAPI images are downloaded from. Only images from here are cached, so the protocol
+/// can't be used to fetch arbitrary URLs.
+const MEDIA_URL: &str = "https://media.valorant-api.com";
+
+// webview2 can't load custom schemes directly, so they're mapped to a localhost subdomain.
+// this is the same mapping `convertFileSrc` uses
+#[cfg(windows)]
+const ASSET_URL: &str = "https://haunt-asset.localhost";
+#[cfg(not(windows))]
+const ASSET_URL: &str = "haunt-asset://localhost";
+
+/// Once the cache grows past this, the oldest images are removed until it's back under.
+const MAX_CACHE_SIZE: u64 = 200 * 1024 * 1024;
+
+/// Rewrites a ValAPI media URL to go through the asset protocol. Anything else is left as-is.
+/// Only for URLs on their way to the webview, since nothing else can load the protocol.
+pub fn asset_url(url: &str) -> String {
+    match url.strip_prefix(MEDIA_URL) {
+        Some(path) => format!("{ASSET_URL}{path}"),
+        None => url.to_string(),
+    }
+}
+
+/// [`asset_url`] in place, for the optional image fields on content.
+pub fn rewrite(url: &mut Option<String>) {
+    if let Some(url) = url {
+        *url = asset_url(url);
+    }
+}
+
+/// Turns a request path, e.g. `/agents/<uuid>/displayicon.png`, into a flat file name.
+/// Rejects anything that isn't a plain media path.
+fn file_name(path: &str) -> Result<String> {
+    let path = path.trim_start_matches('/');
+
+    let valid = !path.is_empty()
+        && !path.contains("..")
+        && path
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '-' | '_' | '.'));
+    if !valid {
+        bail!("Invalid asset path {path}");
+    }
+
+    Ok(path.replace('/', "_"))
+}
+
+fn mime_type(file: &str) -> &'static str {
+    match Path::new(file).extension().and_then(|e| e.to_str()) {
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        Some("gif") => "image/gif",
+        _ => "application/octet-stream",
+    }
+}
+
+async fn download(http: &reqwest::Client, path: &str) -> Result<Vec<u8>> {
+    let url = format!("{MEDIA_URL}{path}");
+
+    debug!("Downloading {url} into the image cache.");
+
+    let bytes = http
+        .get(&url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+
+    Ok(bytes.to_vec())
+}
+
+/// Removes the oldest images until the cache is under [`MAX_CACHE_SIZE`], returning its new size.
+fn evict(dir: &Path) -> Result<u64> {
+    let mut files = fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let meta = entry.metadata().ok()?;
+            let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            meta.is_file().then(|| (entry.path(), meta.len(), modified))
+        })
+        .collect::<Vec<_>>();
+
+    let mut size = files.iter().map(|(_, len, _)| len).sum::<u64>();
+    if size <= MAX_CACHE_SIZE {
+        return Ok(size);
+    }
+
+    // oldest first
+    files.sort_by_key(|(_, _, modified)| *modified);
+
+    for (path, len, _) in files {
+        if size <= MAX_CACHE_SIZE {
+            break;
+        }
+
+        trace!("Evicting {} from the image cache.", path.display());
+        fs::remove_file(&path)?;
+        size -= len;
+    }
+
+    Ok(size)
+}
+
+/// Keeps count of how much the image cache holds, so the directory is only scanned on the first
+/// download and once it's full.
+#[derive(Debug, Default)]
+pub struct CacheSize(Mutex<Option<u64>>);
+
+impl CacheSize {
+    /// Counts a newly written image, evicting the oldest ones if the cache is now too big.
+    fn add(&self, dir: &Path, len: u64) -> Result<()> {
+        let mut size = self.0.lock().unwrap();
+
+        let new_size = match *size {
+            Some(size) if size + len <= MAX_CACHE_SIZE => size + len,
+            // the scan picks up the new image too
+            _ => evict(dir)?,
+        };
+        *size = Some(new_size);
+
+        Ok(())
+    }
+}
+
+/// Returns the image at `path` and its mime type, downloading and caching it on first use.
+///
+/// * `dir` - The cache directory.
+/// * `size` - How much `dir` holds.
+/// * `path` - The media path, e.g. `/agents/<uuid>/displayicon.png`.
+pub async fn load(
+    http: &reqwest::Client,
+    dir: &Path,
+    size: &CacheSize,
+    path: &str,
+) -> Result<(&'static str, Vec<u8>)> {
+    let file = file_name(path)?;
+    let file_path = dir.join(&file);
+
+    if let Ok(bytes) = fs::read(&file_path) {
+        return Ok((mime_type(&file), bytes));
+    }
+
+    let bytes = download(http, path).await?;
+
+    fs::create_dir_all(dir)?;
+    fs::write(&file_path, &bytes)?;
+    if let Err(why) = size.add(dir, bytes.len() as u64) {
+        warn!("Unable to evict old images from the cache: {why}");
+    }
+
+    Ok((mime_type(&file), bytes))
+}
+
+/// Builds the handler for the asset protocol. Images are cached in the app's cache directory.
+/// Downloads run on the async runtime, so the webview isn't blocked while an image loads.
+#[cfg(feature = "tauri")]
+pub fn protocol<R: tauri::Runtime>(
+    http: reqwest::Client,
+) -> impl Fn(&tauri::AppHandle<R>, tauri::http::Request<Vec<u8>>, tauri::UriSchemeResponder)
+       + Send
+       + Sync
+       + 'static {
+    use std::sync::Arc;
+
+    use tauri::{http::Response, Manager};
+
+    let size = Arc::new(CacheSize::default());
+
+    move |app, request, responder| {
+        let uri = request.uri().to_string();
+        // everything after the host, without the query
+        let path = request.uri().path().to_string();
+        let dir = app.path().app_cache_dir().map(|dir| dir.join("images"));
+        let http = http.clone();
+        let size = size.clone();
+
+        tauri::async_runtime::spawn(async move {
+            let loaded = match dir {
+                Ok(dir) => load(&http, &dir, &size, &path).await,
+                Err(why) => Err(why.into()),
+            };
+
+            let response = match loaded {
+                Ok((mime_type, bytes)) => Response::builder()
+                    .header("Content-Type", mime_type)
+                    .header("Cache-Control", "max-age=31536000, immutable")
+                    .status(200)
+                    .body(bytes),
+                Err(why) => {
+                    warn!("Unable to load asset {uri}: {why}");
+                    Response::builder().status(404).body(Vec::new())
+                }
+            };
+
+            match response {
+                Ok(response) => responder.respond(response),
+                Err(why) => warn!("Unable to build the response for {uri}: {why}"),
+            }
+        });
+    }
+}
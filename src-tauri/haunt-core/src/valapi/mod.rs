@@ -0,0 +1,14 @@
+pub mod agents;
+pub mod assets;
+pub mod content;
+pub mod images;
+pub mod seasons;
+mod urls;
+
+/// Base URL for every ValAPI request. Set `HAUNT_VALAPI_URL` to point Haunt at a mirror.
+pub fn base_url() -> String {
+    match std::env::var("HAUNT_VALAPI_URL") {
+        Ok(url) if !url.is_empty() => url.trim_end_matches('/').to_string(),
+        _ => urls::BASE_URL.to_string(),
+    }
+}
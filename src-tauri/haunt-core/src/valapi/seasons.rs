@@ -0,0 +1,191 @@
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use super::urls;
+use crate::Client;
+
+#[derive(Deserialize)]
+struct SeasonsResponse {
+    data: Vec<Season>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Season {
+    pub season_uuid: String,
+    pub competitive_tiers_uuid: String,
+    borders: Option<Vec<Border>>,
+    asset_path: String,
+    start_time: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Border {
+    uuid: String,
+    level: i32,
+    wins_required: i32,
+    display_icon: String,
+    small_icon: Option<String>,
+}
+
+impl Season {
+    /// The seasons response includes both episodes and acts.
+    fn is_act(&self) -> bool {
+        self.asset_path.contains("Act")
+    }
+
+    /// Short name for the act, e.g. `E7 A2`. The competitive seasons endpoint has no display
+    /// names, so it's worked out from the asset path, e.g. `.../Season_Episode7_Act2_DataAsset`.
+    pub fn name(&self) -> String {
+        let file = self.asset_path.rsplit('/').next().unwrap_or_default();
+        let file = file
+            .trim_start_matches("Season_")
+            .trim_end_matches("_DataAsset");
+
+        file.split('_')
+            .map(|part| {
+                if let Some(episode) = part.strip_prefix("Episode") {
+                    format!("E{episode}")
+                } else if let Some(act) = part.strip_prefix("Act") {
+                    format!("A{act}")
+                } else {
+                    part.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Every act that's started, out of every season in the content, most recent first.
+pub fn past_acts(seasons: &[Season]) -> Vec<&Season> {
+    let now = chrono::Utc::now();
+    let mut acts = seasons
+        .iter()
+        .filter(|s| s.is_act() && s.start_time < now)
+        .collect::<Vec<_>>();
+    acts.sort_by_key(|act| std::cmp::Reverse(act.start_time));
+
+    acts
+}
+
+/// The act that's on now, out of every season in the content. None until content has loaded.
+pub fn current_act(seasons: &[Season]) -> Option<&Season> {
+    past_acts(seasons).into_iter().next()
+}
+
+/// Name of the act with the given UUID, or the UUID itself if it isn't in the content.
+pub fn act_name(seasons: &[Season], act: &str) -> String {
+    seasons
+        .iter()
+        .find(|s| s.season_uuid == act)
+        .map_or_else(|| act.to_string(), Season::name)
+}
+
+/// Returns every past competitive act, most recent first.
+pub async fn get_prev_3_seasons(client: &Client) -> Result<Vec<Season>> {
+    let cached = client.content.get().seasons.clone();
+
+    let seasons = match cached.is_empty() {
+        false => cached,
+        // content hasn't loaded yet
+        true => {
+            client
+                .http
+                .get(format!("{}{}", super::base_url(), urls::SEASONS.url))
+                .send()
+                .await?
+                .json::<SeasonsResponse>()
+                .await?
+                .data
+        }
+    };
+
+    // seasons response includes both episodes and acts - we only want past acts
+    Ok(past_acts(&seasons).into_iter().cloned().collect())
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct CompetitiveTierResponseData {
+    /// act uuid
+    uuid: String,
+    tiers: Vec<CompetitiveTierResponseTier>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompetitiveTierResponseTier {
+    /// rank index
+    tier: u32,
+    tier_name: String,
+    small_icon: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompetitiveTier {
+    /// episode UUID
+    pub episode: String,
+    /// UUID of the act the rank is from. Only set on a player's rank history
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub act: String,
+    /// rank index
+    pub tier: u32,
+    pub tier_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
+/// Flattens the per-episode tier lists ValAPI returns into one list.
+pub(super) fn flatten_tiers(data: Vec<CompetitiveTierResponseData>) -> Vec<CompetitiveTier> {
+    data.into_iter()
+        .flat_map(|episode| {
+            episode.tiers.into_iter().map(move |tier| CompetitiveTier {
+                episode: episode.uuid.clone(),
+                act: String::new(),
+                tier: tier.tier,
+                tier_name: tier.tier_name,
+                icon: tier.small_icon,
+            })
+        })
+        .collect()
+}
+
+impl Default for CompetitiveTier {
+    fn default() -> Self {
+        CompetitiveTier {
+            episode: String::new(),
+            act: String::new(),
+            tier: 0,
+            tier_name: String::from("UNRANKED"),
+            icon: None,
+        }
+    }
+}
+
+impl CompetitiveTier {
+    /// Get the competitive tier for a given act and tier index.
+    /// Falls back to the raw tier index if the tiers haven't loaded yet.
+    ///
+    /// * `tiers` - list of competitive tiers
+    /// * `episode` - episode uuid
+    /// * `tier` - rank index
+    pub fn from_act_tier(tiers: &[Self], episode: &str, tier: u32) -> Self {
+        tiers
+            .iter()
+            .find(|t| t.episode == episode && t.tier == tier)
+            .cloned()
+            .unwrap_or_else(|| CompetitiveTier {
+                episode: episode.to_string(),
+                act: String::new(),
+                tier,
+                tier_name: match tier {
+                    0 => String::from("UNRANKED"),
+                    _ => format!("TIER {tier}"),
+                },
+                icon: None,
+            })
+    }
+}
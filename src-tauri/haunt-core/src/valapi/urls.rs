@@ -1,4 +1,3 @@
-#![allow(unused)]
 pub struct Url {
     pub name: &'static str,
     pub suffix: &'static str,
@@ -9,48 +8,60 @@ pub const BASE_URL: &str = "https://valorant-api.com/v1";
 
 pub const MAPS: Url = Url {
     name: "Maps",
-    suffix: "maps.txt",
+    suffix: "maps.json",
     url: "/maps",
 };
 
 pub const AGENTS: Url = Url {
     name: "Agents",
-    suffix: "agents.txt",
-    url: "/agents",
+    suffix: "agents.json",
+    url: "/agents?isPlayableCharacter=true",
 };
 
 pub const SKINS: Url = Url {
     name: "Skins",
-    suffix: "skinchromas.txt",
+    suffix: "skinchromas.json",
     url: "/weapons/skinchromas",
 };
 
 pub const CARDS: Url = Url {
     name: "Cards",
-    suffix: "cards.txt",
+    suffix: "cards.json",
     url: "/playercards",
 };
 
 pub const SPRAYS: Url = Url {
     name: "Sprays",
-    suffix: "sprays.txt",
+    suffix: "sprays.json",
     url: "/sprays",
 };
 
 pub const RANKS: Url = Url {
     name: "Ranks",
-    suffix: "ranks.txt",
+    suffix: "ranks.json",
     url: "/competitivetiers",
 };
 
+pub const TITLES: Url = Url {
+    name: "Titles",
+    suffix: "titles.json",
+    url: "/playertitles",
+};
+
+pub const SEASONS: Url = Url {
+    name: "Seasons",
+    suffix: "seasons.json",
+    url: "/seasons/competitive",
+};
+
 pub const VERSION: Url = Url {
     name: "Version",
-    suffix: "version.txt",
+    suffix: "version.json",
     url: "/version",
 };
 
 pub const GAMEMODES: Url = Url {
     name: "Gamemodes",
-    suffix: "gamemodes.txt",
+    suffix: "gamemodes.json",
     url: "/gamemodes",
 };
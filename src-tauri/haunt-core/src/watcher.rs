@@ -0,0 +1,328 @@
+use std::{fmt::Debug, time::Duration};
+
+use async_trait::async_trait;
+use color_eyre::eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    lobby::{self, ShortMatchData},
+    local::presence,
+    login::{Login, LoginFail},
+    Client,
+};
+
+/// Where the client is in the game loop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ClientState {
+    /// not logged in, or Valorant isn't running
+    Offline,
+    Menus,
+    Queue,
+    Pregame,
+    Ingame,
+    /// the match just ended. only lasts a single poll before going back to menus
+    PostGame,
+}
+
+/// How long to wait between polls in each state, in milliseconds.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollIntervals {
+    pub offline: u64,
+    pub menus: u64,
+    pub queue: u64,
+    pub pregame: u64,
+    pub ingame: u64,
+    pub post_game: u64,
+}
+
+impl Default for PollIntervals {
+    fn default() -> Self {
+        PollIntervals {
+            offline: 10_000,
+            menus: 5_000,
+            queue: 2_000,
+            pregame: 2_000,
+            ingame: 15_000,
+            post_game: 5_000,
+        }
+    }
+}
+
+impl PollIntervals {
+    pub fn for_state(&self, state: ClientState) -> Duration {
+        let ms = match state {
+            ClientState::Offline => self.offline,
+            ClientState::Menus => self.menus,
+            ClientState::Queue => self.queue,
+            ClientState::Pregame => self.pregame,
+            ClientState::Ingame => self.ingame,
+            ClientState::PostGame => self.post_game,
+        };
+
+        Duration::from_millis(ms)
+    }
+}
+
+/// Works out the client state from the user's own presence.
+pub fn state_from_presence(user: &presence::Player) -> ClientState {
+    match user.private.session_loop_state.as_str() {
+        "PREGAME" => ClientState::Pregame,
+        "INGAME" => ClientState::Ingame,
+        _ if user.private.party_state == "MATCHMAKING" => ClientState::Queue,
+        _ => ClientState::Menus,
+    }
+}
+
+/// How a [`LobbyWatcher`] loads and refreshes the current match, so an app can attach its own
+/// data to each player as it's loaded. [`Plain`] loads the lobby as it is.
+#[async_trait]
+pub trait LobbyLoader: Debug + Send + Sync {
+    /// what's attached to each player, as in [`ShortMatchData`]
+    type Extra: Clone + Debug + PartialEq + Send + Sync;
+
+    /// Loads a newly found match.
+    async fn load(
+        &self,
+        client: &Client,
+        login: &Login,
+        match_id: &str,
+        presences: &[presence::Player],
+    ) -> Result<ShortMatchData<Self::Extra>>;
+
+    /// Refetches agent selection and teams for the current match, like [`lobby::quick_update`].
+    async fn update(
+        &self,
+        client: &Client,
+        login: &Login,
+        lobby: &mut ShortMatchData<Self::Extra>,
+        presences: &[presence::Player],
+    ) -> Result<()>;
+}
+
+/// Loads lobbies with nothing attached to the players.
+#[derive(Debug, Default)]
+pub struct Plain;
+
+#[async_trait]
+impl LobbyLoader for Plain {
+    type Extra = ();
+
+    async fn load(
+        &self,
+        client: &Client,
+        login: &Login,
+        match_id: &str,
+        presences: &[presence::Player],
+    ) -> Result<ShortMatchData> {
+        lobby::load(
+            client,
+            &login.entitlements,
+            &login.session,
+            match_id,
+            presences,
+            |_| (),
+        )
+        .await
+    }
+
+    async fn update(
+        &self,
+        client: &Client,
+        login: &Login,
+        lobby: &mut ShortMatchData,
+        presences: &[presence::Player],
+    ) -> Result<()> {
+        lobby::quick_update(
+            client,
+            lobby,
+            &login.entitlements,
+            &login.session,
+            presences,
+        )
+        .await
+    }
+}
+
+/// Something that happened during a [`LobbyWatcher::tick`].
+#[derive(Clone, Debug)]
+pub enum Change<X = ()> {
+    State {
+        from: ClientState,
+        to: ClientState,
+    },
+    /// login failed at a different stage than last time. it's retried every tick
+    LoginFailed(LoginFail),
+    /// a new match was found and fully loaded
+    Loaded(ShortMatchData<X>),
+    /// agent select or teams changed in the current match
+    Updated(ShortMatchData<X>),
+}
+
+/// Follows the client through the game loop, logging in as needed and keeping the current
+/// lobby loaded. Call [`LobbyWatcher::tick`] on an interval, e.g. from [`PollIntervals`].
+#[derive(Debug)]
+pub struct LobbyWatcher<L: LobbyLoader = Plain> {
+    loader: L,
+    login: Option<Login>,
+    login_fail: Option<LoginFail>,
+    state: ClientState,
+    lobby: Option<ShortMatchData<L::Extra>>,
+}
+
+impl Default for LobbyWatcher {
+    fn default() -> Self {
+        LobbyWatcher::new(Plain)
+    }
+}
+
+impl<L: LobbyLoader> LobbyWatcher<L> {
+    pub fn new(loader: L) -> Self {
+        LobbyWatcher {
+            loader,
+            login: None,
+            login_fail: None,
+            state: ClientState::Offline,
+            lobby: None,
+        }
+    }
+
+    pub fn state(&self) -> ClientState {
+        self.state
+    }
+
+    /// The logged in user's PUUID, once login has succeeded.
+    pub fn user(&self) -> Option<&str> {
+        self.login
+            .as_ref()
+            .map(|login| login.session.puuid.as_str())
+    }
+
+    /// The current match. None outside of pregame and ingame.
+    pub fn lobby(&self) -> Option<&ShortMatchData<L::Extra>> {
+        self.lobby.as_ref()
+    }
+
+    fn transition(&mut self, next: ClientState, changes: &mut Vec<Change<L::Extra>>) {
+        if next == self.state {
+            return;
+        }
+
+        info!("Client state changed: {:?} -> {:?}", self.state, next);
+        changes.push(Change::State {
+            from: self.state,
+            to: next,
+        });
+        self.state = next;
+    }
+
+    /// Polls the client once. Returns everything that changed since the last tick.
+    pub async fn tick(&mut self, client: &Client) -> Vec<Change<L::Extra>> {
+        let mut changes = Vec::new();
+
+        if self.login.is_none() {
+            match client.source.get().login(client).await {
+                Ok(login) => {
+                    self.login = Some(login);
+                    self.login_fail = None;
+                }
+                Err(fail) => {
+                    if self.login_fail != Some(fail) {
+                        changes.push(Change::LoginFailed(fail));
+                        self.login_fail = Some(fail);
+                    }
+                    self.transition(ClientState::Offline, &mut changes);
+                    return changes;
+                }
+            }
+        }
+        let Some(login) = self.login.clone() else {
+            return changes;
+        };
+
+        // the user may have closed the game or switched accounts, so log in again next tick
+        let presences = client.source.get().presences(client, &login.lockfile).await;
+        let Some((presences, user)) = presences.ok().and_then(|presences| {
+            let user = presences
+                .iter()
+                .find(|p| p.puuid == login.session.puuid)
+                .cloned()?;
+            Some((presences, user))
+        }) else {
+            self.login = None;
+            self.lobby = None;
+            self.transition(ClientState::Offline, &mut changes);
+            return changes;
+        };
+
+        let mut next = state_from_presence(&user);
+
+        // presence can lag behind, so only trust a match we can actually find
+        let match_id = match next {
+            ClientState::Pregame | ClientState::Ingame => {
+                client
+                    .source
+                    .get()
+                    .find_match(client, &login.entitlements, &login.session)
+                    .await
+            }
+            _ => None,
+        };
+
+        let Some(match_id) = match_id else {
+            next = match (self.state, next) {
+                (ClientState::Ingame, _) => ClientState::PostGame,
+                (_, ClientState::Pregame | ClientState::Ingame) => ClientState::Menus,
+                (_, next) => next,
+            };
+
+            self.lobby = None;
+            self.transition(next, &mut changes);
+            return changes;
+        };
+
+        let previous = self.state;
+        self.transition(next, &mut changes);
+
+        let current = self.lobby.take().filter(|l| l.match_id == match_id);
+        let Some(mut current) = current else {
+            info!("Found new match {match_id}. Loading...");
+            let loaded = self
+                .loader
+                .load(client, &login, &match_id, &presences)
+                .await;
+
+            match loaded {
+                Ok(loaded) => {
+                    changes.push(Change::Loaded(loaded.clone()));
+                    self.lobby = Some(loaded);
+                }
+                Err(why) => warn!("Unable to load match {match_id}: {why}. Retrying next poll."),
+            }
+
+            return changes;
+        };
+
+        // agents only change during agent select, but teams are finalised once the match starts
+        if next == ClientState::Pregame || previous != next {
+            let before = current.clone();
+            let updated = self
+                .loader
+                .update(client, &login, &mut current, &presences)
+                .await;
+
+            match updated {
+                Ok(_) if current != before => changes.push(Change::Updated(current.clone())),
+                Ok(_) => trace!("Match {match_id} unchanged."),
+                Err(why) => {
+                    warn!("Unable to update match {match_id}: {why}. Reloading next poll.");
+                    return changes;
+                }
+            }
+        }
+
+        self.lobby = Some(current);
+        changes
+    }
+}
@@ -0,0 +1,340 @@
+//! Runs login and match loading against haunt-sim through haunt-core, i.e. `login::login`,
+//! `lobby::load` and `lobby::quick_update`.
+//!
+//! The app's commands and background watcher are out of scope. They need a Tauri runtime, and what
+//! they add on top (the match cache, events, encounters, notes and streamer mode) isn't covered
+//! here.
+
+use std::{path::PathBuf, sync::OnceLock};
+
+use haunt_core::{
+    friends,
+    lobby::{self, ShortMatchData},
+    local::presence::{self, Availability},
+    login::{self, Login, LoginFail},
+    pvp,
+    watcher::{self, ClientState},
+    Client,
+};
+use haunt_sim::{PresenceSchema, Scenario, Simulator, Step, LEAGUE_FRIEND};
+use tokio::sync::{Mutex, MutexGuard};
+
+/// Haunt finds the simulator through process-wide env vars, so tests take turns.
+fn env_lock() -> &'static Mutex<()> {
+    static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+    LOCK.get_or_init(|| Mutex::new(()))
+}
+
+struct Setup {
+    sim: Simulator,
+    client: Client,
+    local_app_data: PathBuf,
+    _guard: MutexGuard<'static, ()>,
+}
+
+impl Drop for Setup {
+    fn drop(&mut self) {
+        _ = std::fs::remove_dir_all(&self.local_app_data);
+    }
+}
+
+async fn setup() -> Setup {
+    setup_with(Scenario::default()).await
+}
+
+async fn setup_with(scenario: Scenario) -> Setup {
+    let guard = env_lock().lock().await;
+    let sim = Simulator::start(scenario).await.unwrap();
+
+    let local_app_data = std::env::temp_dir().join(format!(
+        "haunt-sim-{}-{}",
+        std::process::id(),
+        sim.url().rsplit(':').next().unwrap()
+    ));
+    sim.write_lockfile(&local_app_data).unwrap();
+
+    std::env::set_var("LOCALAPPDATA", &local_app_data);
+    for (var, value) in sim.env() {
+        std::env::set_var(var, value);
+    }
+
+    Setup {
+        sim,
+        client: Client::new().unwrap(),
+        local_app_data,
+        _guard: guard,
+    }
+}
+
+impl Setup {
+    /// Advances until `phase` matches the current step.
+    fn advance_to(&self, phase: impl Fn(&Step) -> bool) {
+        while !phase(&self.sim.step()) {
+            let before = self.sim.step();
+            assert_ne!(
+                self.sim.advance(),
+                before,
+                "the scenario never reaches that step"
+            );
+        }
+    }
+
+    async fn login(&self) -> Login {
+        login::login(&self.client).await.unwrap()
+    }
+
+    async fn presences(&self, login: &Login) -> Vec<presence::Player> {
+        presence::get_presences(&login.lockfile, &self.client.offline_http)
+            .await
+            .unwrap()
+    }
+
+    async fn find_match(&self, login: &Login) -> Option<String> {
+        pvp::find_match_id(
+            &login.entitlements,
+            &self.client.http,
+            &self.client.rate_limiter,
+            &login.session,
+        )
+        .await
+    }
+
+    async fn load_match(&self, login: &Login) -> ShortMatchData {
+        let match_id = self.find_match(login).await.expect("should be in a match");
+        let presences = self.presences(login).await;
+
+        lobby::load(
+            &self.client,
+            &login.entitlements,
+            &login.session,
+            &match_id,
+            &presences,
+            |_| (),
+        )
+        .await
+        .unwrap()
+    }
+}
+
+fn player<'a>(lobby: &'a ShortMatchData, puuid: &str) -> &'a lobby::ShortPlayer {
+    lobby.players.iter().find(|p| p.uuid == puuid).unwrap()
+}
+
+#[tokio::test]
+async fn login_fails_without_a_lockfile() {
+    let setup = setup().await;
+    std::fs::remove_dir_all(&setup.local_app_data).unwrap();
+
+    let fail = login::login(&setup.client).await.unwrap_err();
+    assert_eq!(fail, LoginFail::Lockfile);
+}
+
+#[tokio::test]
+async fn login_finds_the_user() {
+    let setup = setup().await;
+    let scenario = setup.sim.scenario();
+
+    let login = setup.login().await;
+    assert_eq!(login.session.puuid, scenario.user().puuid);
+    assert_eq!(login.session.version, scenario.client_version);
+    assert_eq!(login.session.region.to_string(), scenario.deployment);
+    assert_eq!(login.user.game_name, scenario.user().game_name);
+    assert_eq!(
+        watcher::state_from_presence(&login.user),
+        ClientState::Menus
+    );
+}
+
+#[tokio::test]
+async fn no_match_until_agent_select() {
+    let setup = setup().await;
+    let login = setup.login().await;
+    assert_eq!(setup.find_match(&login).await, None);
+
+    setup.advance_to(|s| *s == Step::Queue);
+    let presences = setup.presences(&login).await;
+    let user = presences
+        .iter()
+        .find(|p| p.puuid == login.session.puuid)
+        .unwrap();
+    assert_eq!(watcher::state_from_presence(user), ClientState::Queue);
+    assert_eq!(setup.find_match(&login).await, None);
+}
+
+#[tokio::test]
+async fn presences_skip_other_games_and_undecodable_ones() {
+    let setup = setup().await;
+    let scenario = setup.sim.scenario();
+    let login = setup.login().await;
+
+    let presences = setup.presences(&login).await;
+    let visible = scenario
+        .players
+        .iter()
+        .enumerate()
+        .filter(|(i, p)| *i == 0 || p.visible)
+        .map(|(_, p)| p.puuid.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        presences
+            .iter()
+            .map(|p| p.puuid.clone())
+            .collect::<Vec<_>>(),
+        visible
+    );
+}
+
+#[tokio::test]
+async fn nested_presences_decode_like_flat_ones() {
+    let setup = setup_with(Scenario {
+        presence_schema: PresenceSchema::Nested,
+        ..Default::default()
+    })
+    .await;
+    let scenario = setup.sim.scenario();
+    setup.advance_to(|s| *s == Step::Queue);
+
+    let login = setup.login().await;
+    assert_eq!(
+        watcher::state_from_presence(&login.user),
+        ClientState::Queue
+    );
+
+    let presences = setup.presences(&login).await;
+    for presence in &presences {
+        let expected = scenario.player(&presence.puuid).unwrap();
+        assert_eq!(presence.private.party_id, expected.party_id);
+        assert_eq!(presence.private.account_level, expected.account_level);
+        assert_eq!(presence.private.queue_id, scenario.queue_id);
+    }
+}
+
+#[tokio::test]
+async fn friends_show_what_everyone_is_doing() {
+    let setup = setup().await;
+    let scenario = setup.sim.scenario();
+    setup.advance_to(|s| *s == Step::Queue);
+
+    let login = setup.login().await;
+    let friends = friends::load(&setup.client, &login.lockfile).await.unwrap();
+
+    let visible = scenario.players.iter().skip(1).filter(|p| p.visible);
+    assert_eq!(friends.len(), visible.clone().count() + 1);
+    for expected in visible {
+        let friend = friends.iter().find(|f| f.puuid == expected.puuid).unwrap();
+        let party_size = scenario
+            .players
+            .iter()
+            .filter(|p| p.party_id == expected.party_id)
+            .count();
+
+        assert_eq!(friend.availability, Availability::Online);
+        assert_eq!(friend.state, ClientState::Queue);
+        assert_eq!(friend.map, None);
+        assert_eq!(friend.party_size, Some(party_size as u32));
+    }
+
+    // online, but not in Valorant
+    let league = friends.last().unwrap();
+    assert_eq!(league.puuid, LEAGUE_FRIEND);
+    assert_eq!(league.availability, Availability::Away);
+    assert_eq!(league.state, ClientState::Offline);
+}
+
+#[tokio::test]
+async fn load_match_in_agent_select() {
+    let setup = setup().await;
+    let scenario = setup.sim.scenario();
+    setup.advance_to(|s| matches!(s, Step::Pregame { .. }));
+
+    let login = setup.login().await;
+    let lobby = setup.load_match(&login).await;
+
+    assert_eq!(lobby.match_id, scenario.match_id);
+    assert!(!lobby.ingame);
+    assert_eq!(lobby.players.len(), scenario.players.len());
+    assert!(lobby.players.iter().all(|p| !p.locked));
+
+    for expected in &scenario.players {
+        let player = player(&lobby, &expected.puuid);
+        let name = format!("{} #{}", expected.game_name, expected.game_tag);
+
+        // incognito players go by their agent, and only friends' parties are known
+        assert_eq!(player.name == name, !expected.incognito);
+        assert_eq!(player.account_level.is_none(), expected.hide_account_level);
+        assert_eq!(
+            player.rank_history.len(),
+            expected.ranks.iter().filter(|r| **r > 0).count()
+        );
+        match expected.visible {
+            true => assert_eq!(player.party_id, expected.party_id),
+            false => assert_eq!(player.party_id, ""),
+        }
+    }
+}
+
+#[tokio::test]
+async fn quick_update_match_follows_agent_select() {
+    let setup = setup().await;
+    setup.advance_to(|s| matches!(s, Step::Pregame { locked } if locked.is_empty()));
+
+    let login = setup.login().await;
+    let mut lobby = setup.load_match(&login).await;
+
+    let Step::Pregame { locked } = setup.sim.advance() else {
+        panic!("the default scenario locks agents in a second pregame step");
+    };
+    let presences = setup.presences(&login).await;
+    lobby::quick_update(
+        &setup.client,
+        &mut lobby,
+        &login.entitlements,
+        &login.session,
+        &presences,
+    )
+    .await
+    .unwrap();
+
+    for player in &lobby.players {
+        assert_eq!(player.locked, locked.contains(&player.uuid));
+    }
+}
+
+#[tokio::test]
+async fn quick_update_match_once_ingame() {
+    let setup = setup().await;
+    setup.advance_to(|s| matches!(s, Step::Pregame { .. }));
+
+    let login = setup.login().await;
+    let mut lobby = setup.load_match(&login).await;
+
+    setup.advance_to(|s| *s == Step::Ingame);
+    assert_eq!(setup.find_match(&login).await, Some(lobby.match_id.clone()));
+
+    let presences = setup.presences(&login).await;
+    lobby::quick_update(
+        &setup.client,
+        &mut lobby,
+        &login.entitlements,
+        &login.session,
+        &presences,
+    )
+    .await
+    .unwrap();
+
+    assert!(lobby.ingame);
+    assert!(lobby.players.iter().all(|p| p.locked));
+}
+
+#[tokio::test]
+async fn match_is_gone_after_it_ends() {
+    let setup = setup().await;
+    setup.advance_to(|s| *s == Step::PostGame);
+
+    let login = setup.login().await;
+    assert_eq!(setup.find_match(&login).await, None);
+    assert_eq!(
+        watcher::state_from_presence(&login.user),
+        ClientState::Menus
+    );
+}
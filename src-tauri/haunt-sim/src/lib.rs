@@ -0,0 +1,113 @@
+//! A scripted stand-in for the Riot client and the pd/glz services. It writes a lockfile and
+//! serves the endpoints Haunt uses, stepping through a [`Scenario`] from menus to the end of a
+//! match. Point Haunt at it with the variables from [`Simulator::env`].
+
+#[macro_use]
+extern crate tracing;
+
+use std::{
+    net::{SocketAddr, TcpListener},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use color_eyre::eyre::Result;
+
+mod routes;
+pub mod scenario;
+
+pub use routes::LEAGUE_FRIEND;
+use routes::{Shared, SimState};
+pub use scenario::{PresenceSchema, Scenario, Step};
+
+/// A running simulator. It stops with the tokio runtime it was started on.
+pub struct Simulator {
+    addr: SocketAddr,
+    password: String,
+    state: Shared,
+}
+
+impl Simulator {
+    /// Starts serving the scenario on a free local port, at its first step.
+    pub async fn start(scenario: Scenario) -> Result<Self> {
+        scenario.validate()?;
+
+        let password = format!(
+            "{:x}",
+            SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos()
+        );
+        let state = Arc::new(Mutex::new(SimState {
+            scenario,
+            step: 0,
+            password: password.clone(),
+        }));
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let server = axum::Server::from_tcp(listener)?
+            .serve(routes::router(state.clone()).into_make_service());
+
+        tokio::spawn(async move {
+            if let Err(why) = server.await {
+                error!("Simulator stopped: {why}");
+            }
+        });
+        info!("Simulator listening on {addr}");
+
+        Ok(Simulator {
+            addr,
+            password,
+            state,
+        })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Lockfile contents pointing at the simulator. Unlike the real client it's plain http.
+    pub fn lockfile(&self) -> String {
+        format!(
+            "Riot Client:{}:{}:{}:http",
+            std::process::id(),
+            self.addr.port(),
+            self.password
+        )
+    }
+
+    /// Writes the lockfile where Haunt looks for it when `LOCALAPPDATA` is `local_app_data`.
+    pub fn write_lockfile(&self, local_app_data: &Path) -> Result<PathBuf> {
+        let dir = local_app_data
+            .join("Riot Games")
+            .join("Riot Client")
+            .join("Config");
+        std::fs::create_dir_all(&dir)?;
+
+        let path = dir.join("lockfile");
+        std::fs::write(&path, self.lockfile())?;
+        Ok(path)
+    }
+
+    /// Environment variables that send Haunt's pd, glz and ValAPI requests here.
+    pub fn env(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("HAUNT_GLZ_URL", format!("{}/glz", self.url())),
+            ("HAUNT_PD_URL", format!("{}/pd", self.url())),
+            ("HAUNT_VALAPI_URL", format!("{}/valapi/v1", self.url())),
+        ]
+    }
+
+    pub fn scenario(&self) -> Scenario {
+        self.state.lock().unwrap().scenario.clone()
+    }
+
+    pub fn step(&self) -> Step {
+        self.state.lock().unwrap().step().clone()
+    }
+
+    /// Moves to the next step, staying on the last one once it's reached.
+    pub fn advance(&self) -> Step {
+        self.state.lock().unwrap().advance()
+    }
+}
@@ -0,0 +1,84 @@
+use std::{path::PathBuf, time::Duration};
+
+use clap::Parser;
+use color_eyre::eyre::Result;
+use haunt_sim::{Scenario, Simulator, Step};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+
+/// Pretends to be the Riot client and pd/glz, stepping through a scripted match.
+#[derive(Parser)]
+#[command(name = "haunt-sim", version)]
+struct Args {
+    /// Scenario to play. Defaults to a competitive match on Ascent
+    #[arg(long)]
+    scenario: Option<PathBuf>,
+    /// Where to write the lockfile. Haunt reads it from LOCALAPPDATA, so point that here too
+    #[arg(long, env = "LOCALAPPDATA", default_value = "haunt-sim")]
+    local_app_data: PathBuf,
+    /// Move to the next step every this many seconds. Otherwise press enter to advance
+    #[arg(long)]
+    step_secs: Option<u64>,
+}
+
+fn print_step(step: &Step) {
+    println!("Step: {step:?}");
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    color_eyre::install()?;
+    tracing_subscriber::registry()
+        .with(fmt::layer())
+        .with(EnvFilter::from_default_env())
+        .init();
+
+    let args = Args::parse();
+
+    let scenario = match &args.scenario {
+        Some(path) => Scenario::load(path)?,
+        None => Scenario::default(),
+    };
+    let sim = Simulator::start(scenario).await?;
+
+    // Haunt may run from anywhere, so it needs the full path
+    let local_app_data = std::env::current_dir()?.join(&args.local_app_data);
+    let lockfile = sim.write_lockfile(&local_app_data)?;
+    println!("Wrote {}", lockfile.display());
+
+    println!("Point Haunt at the simulator with:");
+    println!("  export LOCALAPPDATA=\"{}\"", local_app_data.display());
+    for (var, value) in sim.env() {
+        println!("  export {var}={value}");
+    }
+    println!(
+        "POST {}/_sim/advance also moves to the next step.",
+        sim.url()
+    );
+    print_step(&sim.step());
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        match args.step_secs {
+            Some(secs) => tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(secs)) => (),
+                _ = tokio::signal::ctrl_c() => break,
+            },
+            None => tokio::select! {
+                line = lines.next_line() => {
+                    // stdin closed, so nothing can advance the scenario but the endpoint
+                    if line?.is_none() {
+                        tokio::signal::ctrl_c().await?;
+                        break;
+                    }
+                },
+                _ = tokio::signal::ctrl_c() => break,
+            },
+        }
+
+        print_step(&sim.advance());
+    }
+
+    std::fs::remove_file(lockfile)?;
+    Ok(())
+}
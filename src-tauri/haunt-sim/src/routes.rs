@@ -0,0 +1,493 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    extract::{Path, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    routing::{get, post, put},
+    Json, Router,
+};
+use base64::prelude::*;
+use serde_json::{json, Value};
+
+use crate::scenario::{PresenceSchema, Scenario, ScenarioPlayer, Step};
+
+/// A friend playing League, whose presence Haunt has to skip.
+pub const LEAGUE_FRIEND: &str = "5157a3e0-0000-4000-8000-00000000001e";
+
+/// Handed out by the entitlements endpoint, and expected on every pd/glz request.
+pub(crate) const ACCESS_TOKEN: &str = "haunt-sim-access-token";
+pub(crate) const ENTITLEMENTS_JWT: &str = "haunt-sim-entitlements-jwt";
+
+pub(crate) struct SimState {
+    pub scenario: Scenario,
+    /// index into the scenario's steps
+    pub step: usize,
+    /// from the lockfile. the local endpoints expect it as basic auth
+    pub password: String,
+}
+
+impl SimState {
+    pub fn step(&self) -> &Step {
+        &self.scenario.steps[self.step]
+    }
+
+    /// Moves to the next step, staying on the last one once it's reached.
+    pub fn advance(&mut self) -> Step {
+        self.step = (self.step + 1).min(self.scenario.steps.len() - 1);
+        self.step().clone()
+    }
+}
+
+pub(crate) type Shared = Arc<Mutex<SimState>>;
+
+type Reply = Result<Json<Value>, StatusCode>;
+
+pub(crate) fn router(state: Shared) -> Router {
+    Router::new()
+        // local client api, found through the lockfile
+        .route("/entitlements/v1/token", get(entitlements))
+        .route("/product-session/v1/external-sessions", get(sessions))
+        .route("/chat/v4/presences", get(presences))
+        .route("/chat/v4/friends", get(friends))
+        // glz
+        .route("/glz/core-game/v1/players/:puuid", get(ingame_player))
+        .route("/glz/core-game/v1/matches/:match_id", get(ingame_match))
+        .route("/glz/pregame/v1/players/:puuid", get(pregame_player))
+        .route("/glz/pregame/v1/matches/:match_id", get(pregame_match))
+        // pd
+        .route("/pd/mmr/v1/players/:puuid", get(mmr))
+        .route("/pd/name-service/v2/players", put(names))
+        // ValAPI. only what login and rank history need, everything else is empty
+        .route("/valapi/v1/version", get(version))
+        .route("/valapi/v1/seasons/competitive", get(seasons))
+        .route("/valapi/v1/*rest", get(empty_content))
+        .route("/_sim/advance", post(advance))
+        .with_state(state)
+}
+
+fn check_basic(state: &SimState, headers: &HeaderMap) -> Result<(), StatusCode> {
+    let expected = format!(
+        "Basic {}",
+        BASE64_STANDARD.encode(format!("riot:{}", state.password))
+    );
+
+    match headers.get(AUTHORIZATION) {
+        Some(auth) if auth == expected.as_str() => Ok(()),
+        _ => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+fn check_bearer(headers: &HeaderMap) -> Result<(), StatusCode> {
+    let bearer = format!("Bearer {ACCESS_TOKEN}");
+    let authorised = headers
+        .get(AUTHORIZATION)
+        .is_some_and(|a| a == bearer.as_str())
+        && headers
+            .get("X-Riot-Entitlements-JWT")
+            .is_some_and(|jwt| jwt == ENTITLEMENTS_JWT);
+
+    match authorised {
+        true => Ok(()),
+        false => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+async fn entitlements(State(state): State<Shared>, headers: HeaderMap) -> Reply {
+    let state = state.lock().unwrap();
+    check_basic(&state, &headers)?;
+
+    Ok(Json(json!({
+        "accessToken": ACCESS_TOKEN,
+        "token": ENTITLEMENTS_JWT,
+        "subject": state.scenario.user().puuid,
+        "entitlements": [],
+        "issuer": "https://entitlements.auth.riotgames.com",
+    })))
+}
+
+async fn sessions(State(state): State<Shared>, headers: HeaderMap) -> Reply {
+    let state = state.lock().unwrap();
+    check_basic(&state, &headers)?;
+
+    let scenario = &state.scenario;
+    Ok(Json(json!({
+        "host_app": {
+            "launchConfiguration": { "arguments": [] },
+            "productId": "riot_client",
+        },
+        "valorant": {
+            "launchConfiguration": {
+                "arguments": [
+                    format!("-ares-deployment={}", scenario.deployment),
+                    format!("-subject={}", scenario.user().puuid),
+                ],
+            },
+            "productId": "valorant",
+        },
+    })))
+}
+
+fn presence(scenario: &Scenario, step: &Step, player: &ScenarioPlayer) -> Value {
+    let match_map = match step.in_match() {
+        true => scenario.map.as_str(),
+        false => "",
+    };
+    let competitive_tier = player.ranks.last().copied().unwrap_or(0);
+    let party_size = scenario
+        .players
+        .iter()
+        .filter(|p| p.party_id == player.party_id)
+        .count();
+    let private = match scenario.presence_schema {
+        PresenceSchema::Flat => json!({
+            "isValid": true,
+            "matchMap": match_map,
+            "partyId": player.party_id,
+            "playerCardId": "",
+            "playerTitleId": "",
+            "preferredLevelBorderId": "",
+            "accountLevel": player.account_level,
+            "competitiveTier": competitive_tier,
+            "queueId": scenario.queue_id,
+            "sessionLoopState": step.loop_state(),
+            "partyState": step.party_state(),
+            "partySize": party_size,
+            "partyOwnerMatchScoreAllyTeam": 0,
+            "partyOwnerMatchScoreEnemyTeam": 0,
+        }),
+        PresenceSchema::Nested => json!({
+            "isValid": true,
+            "matchPresenceData": {
+                "sessionLoopState": step.loop_state(),
+                "provisioningFlow": "Matchmaking",
+                "matchMap": match_map,
+                "queueId": scenario.queue_id,
+            },
+            "partyPresenceData": {
+                "partyId": player.party_id,
+                "isPartyOwner": true,
+                "partyState": step.party_state(),
+                "partySize": party_size,
+                "partyOwnerMatchScoreAllyTeam": 0,
+                "partyOwnerMatchScoreEnemyTeam": 0,
+            },
+            "playerPresenceData": {
+                "playerCardId": "",
+                "playerTitleId": "",
+                "preferredLevelBorderId": "",
+                "accountLevel": player.account_level,
+                "competitiveTier": competitive_tier,
+            },
+        }),
+    };
+
+    json!({
+        "puuid": player.puuid,
+        "game_name": player.game_name,
+        "game_tag": player.game_tag,
+        "product": "valorant",
+        "state": match step.in_match() {
+            true => "dnd",
+            false => "chat",
+        },
+        "private": BASE64_STANDARD.encode(private.to_string()),
+    })
+}
+
+async fn presences(State(state): State<Shared>, headers: HeaderMap) -> Reply {
+    let state = state.lock().unwrap();
+    check_basic(&state, &headers)?;
+
+    // everyone visible is in the user's match, so they all follow the same steps
+    let scenario = &state.scenario;
+    let mut presences = scenario
+        .players
+        .iter()
+        .enumerate()
+        .filter(|(i, p)| *i == 0 || p.visible)
+        .map(|(_, p)| presence(scenario, state.step(), p))
+        .collect::<Vec<_>>();
+
+    // friends outside the match, whose presences Haunt has to skip
+    presences.extend([
+        json!({
+            "puuid": LEAGUE_FRIEND,
+            "game_name": "Summoner",
+            "game_tag": "EUW",
+            "product": "league_of_legends",
+            "state": "away",
+            "private": "<body><gameStatus>inGame</gameStatus></body>",
+        }),
+        json!({
+            "puuid": "5157a3e0-0000-4000-8000-00000000002e",
+            "game_name": "Loading",
+            "game_tag": "0000",
+            "product": "valorant",
+            "private": "",
+        }),
+        json!({
+            "puuid": "5157a3e0-0000-4000-8000-00000000003e",
+            "product": "bacon",
+        }),
+    ]);
+
+    Ok(Json(json!({ "presences": presences })))
+}
+
+async fn friends(State(state): State<Shared>, headers: HeaderMap) -> Reply {
+    let state = state.lock().unwrap();
+    check_basic(&state, &headers)?;
+
+    // everyone visible besides the user, and the friend playing League
+    let mut friends = state
+        .scenario
+        .players
+        .iter()
+        .skip(1)
+        .filter(|p| p.visible)
+        .map(|p| (p.puuid.as_str(), p.game_name.as_str(), p.game_tag.as_str()))
+        .collect::<Vec<_>>();
+    friends.push((LEAGUE_FRIEND, "Summoner", "EUW"));
+
+    let friends = friends
+        .into_iter()
+        .map(|(puuid, game_name, game_tag)| {
+            json!({
+                "puuid": puuid,
+                "pid": format!("{puuid}@na1.pvp.net"),
+                "game_name": game_name,
+                "game_tag": game_tag,
+                "note": "",
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Json(json!({ "friends": friends })))
+}
+
+/// The match id, if the player is in the scenario and the match is in the phase being asked about.
+fn current_match(state: &SimState, puuid: &str, in_phase: bool) -> Reply {
+    match in_phase && state.scenario.player(puuid).is_some() {
+        true => Ok(Json(json!({
+            "Subject": puuid,
+            "MatchID": state.scenario.match_id,
+        }))),
+        false => Err(StatusCode::NOT_FOUND),
+    }
+}
+
+fn identity(player: &ScenarioPlayer) -> Value {
+    json!({
+        "Subject": player.puuid,
+        "PlayerCardID": "",
+        "PlayerTitleID": "",
+        "AccountLevel": player.account_level,
+        "PreferredLevelBorderID": "",
+        "Incognito": player.incognito,
+        "HideAccountLevel": player.hide_account_level,
+    })
+}
+
+async fn ingame_player(
+    State(state): State<Shared>,
+    Path(puuid): Path<String>,
+    headers: HeaderMap,
+) -> Reply {
+    check_bearer(&headers)?;
+    let state = state.lock().unwrap();
+    current_match(&state, &puuid, *state.step() == Step::Ingame)
+}
+
+async fn pregame_player(
+    State(state): State<Shared>,
+    Path(puuid): Path<String>,
+    headers: HeaderMap,
+) -> Reply {
+    check_bearer(&headers)?;
+    let state = state.lock().unwrap();
+    current_match(&state, &puuid, matches!(state.step(), Step::Pregame { .. }))
+}
+
+async fn ingame_match(
+    State(state): State<Shared>,
+    Path(match_id): Path<String>,
+    headers: HeaderMap,
+) -> Reply {
+    check_bearer(&headers)?;
+    let state = state.lock().unwrap();
+
+    let scenario = &state.scenario;
+    if *state.step() != Step::Ingame || match_id != scenario.match_id {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let players = scenario
+        .players
+        .iter()
+        .map(|p| {
+            json!({
+                "Subject": p.puuid,
+                "TeamID": p.team,
+                "CharacterID": p.agent,
+                "PlayerIdentity": identity(p),
+                "IsCoach": false,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Json(json!({
+        "MatchID": scenario.match_id,
+        "MapID": scenario.map,
+        "ModeID": scenario.mode,
+        "Players": players,
+    })))
+}
+
+async fn pregame_match(
+    State(state): State<Shared>,
+    Path(match_id): Path<String>,
+    headers: HeaderMap,
+) -> Reply {
+    check_bearer(&headers)?;
+    let state = state.lock().unwrap();
+
+    let scenario = &state.scenario;
+    let Step::Pregame { locked } = state.step() else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+    if match_id != scenario.match_id {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let teams = ["Blue", "Red"]
+        .into_iter()
+        .map(|team| {
+            let players = scenario
+                .players
+                .iter()
+                .filter(|p| p.team == team)
+                .map(|p| {
+                    let selection = match locked.contains(&p.puuid) {
+                        true => "Locked",
+                        false => "Selected",
+                    };
+
+                    json!({
+                        "Subject": p.puuid,
+                        "CharacterID": p.agent,
+                        "CharacterSelectionState": selection,
+                        "PlayerIdentity": identity(p),
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            json!({ "TeamID": team, "Players": players })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Json(json!({
+        "ID": scenario.match_id,
+        "MapID": scenario.map,
+        "Mode": scenario.mode,
+        "Teams": teams,
+        "ObserverSubjects": [],
+        "MatchCoaches": [],
+    })))
+}
+
+async fn mmr(State(state): State<Shared>, Path(puuid): Path<String>, headers: HeaderMap) -> Reply {
+    check_bearer(&headers)?;
+    let state = state.lock().unwrap();
+
+    let scenario = &state.scenario;
+    let player = scenario.player(&puuid).ok_or(StatusCode::NOT_FOUND)?;
+
+    let seasons = scenario
+        .acts
+        .iter()
+        .zip(&player.ranks)
+        .filter(|(_, tier)| **tier > 0)
+        .map(|(act, tier)| {
+            let info = json!({ "SeasonID": act.season_uuid, "CompetitiveTier": tier });
+            (act.season_uuid.clone(), info)
+        })
+        .collect::<HashMap<_, _>>();
+
+    Ok(Json(json!({
+        "Subject": puuid,
+        "QueueSkills": {
+            "competitive": { "SeasonalInfoBySeasonID": seasons },
+        },
+    })))
+}
+
+// Haunt sends the puuids without a content type, so this can't use the Json extractor
+async fn names(State(state): State<Shared>, headers: HeaderMap, body: String) -> Reply {
+    check_bearer(&headers)?;
+    let puuids: Vec<String> = serde_json::from_str(&body).map_err(|_| StatusCode::BAD_REQUEST)?;
+    let state = state.lock().unwrap();
+
+    let names = puuids
+        .iter()
+        .filter_map(|puuid| state.scenario.player(puuid))
+        .map(|p| {
+            json!({
+                "DisplayName": "",
+                "Subject": p.puuid,
+                "GameName": p.game_name,
+                "TagLine": p.game_tag,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Json(Value::Array(names)))
+}
+
+async fn version(State(state): State<Shared>) -> Json<Value> {
+    let state = state.lock().unwrap();
+
+    Json(json!({
+        "status": 200,
+        "data": {
+            "manifestId": "HAUNTSIM",
+            "version": "haunt-sim",
+            "riotClientVersion": state.scenario.client_version,
+        },
+    }))
+}
+
+async fn seasons(State(state): State<Shared>) -> Json<Value> {
+    let state = state.lock().unwrap();
+
+    let acts = state
+        .scenario
+        .acts
+        .iter()
+        .enumerate()
+        .map(|(i, act)| {
+            json!({
+                "uuid": act.season_uuid,
+                "seasonUuid": act.season_uuid,
+                "competitiveTiersUuid": act.competitive_tiers_uuid,
+                "startTime": act.start_time,
+                "borders": null,
+                "assetPath": format!("ShooterGame/Content/Seasons/Season_Act{}_DataAsset", i + 1),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    Json(json!({ "status": 200, "data": acts }))
+}
+
+async fn empty_content() -> Json<Value> {
+    Json(json!({ "status": 200, "data": [] }))
+}
+
+async fn advance(State(state): State<Shared>) -> Json<Step> {
+    let step = state.lock().unwrap().advance();
+    info!("Advanced to {step:?}");
+    Json(step)
+}
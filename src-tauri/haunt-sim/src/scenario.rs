@@ -0,0 +1,151 @@
+use std::path::Path;
+
+use color_eyre::eyre::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// The scenario used when none is given: a competitive match on Ascent with two parties.
+const DEFAULT: &str = include_str!("../scenarios/competitive.json");
+
+/// A single match, and the steps the client goes through around it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scenario {
+    pub match_id: String,
+    /// map url, e.g. /Game/Maps/Ascent/Ascent
+    pub map: String,
+    /// game mode url, as the match endpoints return it
+    pub mode: String,
+    /// queue id, as presences return it
+    pub queue_id: String,
+    /// the user's -ares-deployment, e.g. na
+    pub deployment: String,
+    /// riot client version reported by the ValAPI version endpoint
+    pub client_version: String,
+    /// competitive acts, oldest first
+    pub acts: Vec<Act>,
+    /// everyone in the match. the first player is the user
+    pub players: Vec<ScenarioPlayer>,
+    pub steps: Vec<Step>,
+    /// how private presences are laid out
+    #[serde(default)]
+    pub presence_schema: PresenceSchema,
+}
+
+/// Clients from before 2024 send private presences flat. Newer ones group them by what they're
+/// about.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PresenceSchema {
+    #[default]
+    Flat,
+    /// under matchPresenceData, partyPresenceData and playerPresenceData
+    Nested,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Act {
+    pub season_uuid: String,
+    pub competitive_tiers_uuid: String,
+    /// RFC 3339. Haunt ignores acts that haven't started yet
+    pub start_time: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioPlayer {
+    pub puuid: String,
+    pub game_name: String,
+    pub game_tag: String,
+    /// Blue or Red
+    pub team: String,
+    /// agent uuid
+    pub agent: String,
+    pub party_id: String,
+    pub account_level: u32,
+    /// competitive tier in each of the scenario's acts, 0 if they didn't play it
+    #[serde(default)]
+    pub ranks: Vec<u32>,
+    #[serde(default)]
+    pub incognito: bool,
+    #[serde(default)]
+    pub hide_account_level: bool,
+    /// whether the user can see their presence, i.e. they're a friend or in the user's party.
+    /// the user's own presence is always visible
+    #[serde(default)]
+    pub visible: bool,
+}
+
+/// Where the client is. The simulator moves through these in order.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "phase", rename_all = "camelCase")]
+pub enum Step {
+    Menus,
+    /// in menus, with the party matchmaking
+    Queue,
+    /// agent select. everyone hovers their agent until they're listed in `locked`
+    Pregame {
+        #[serde(default)]
+        locked: Vec<String>,
+    },
+    Ingame,
+    /// back in menus once the match is over
+    PostGame,
+}
+
+impl Step {
+    /// sessionLoopState in the user's presence
+    pub(crate) fn loop_state(&self) -> &'static str {
+        match self {
+            Step::Pregame { .. } => "PREGAME",
+            Step::Ingame => "INGAME",
+            _ => "MENUS",
+        }
+    }
+
+    /// partyState in the user's presence
+    pub(crate) fn party_state(&self) -> &'static str {
+        match self {
+            Step::Queue => "MATCHMAKING",
+            _ => "DEFAULT",
+        }
+    }
+
+    pub(crate) fn in_match(&self) -> bool {
+        matches!(self, Step::Pregame { .. } | Step::Ingame)
+    }
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        serde_json::from_str(DEFAULT).expect("the built in scenario is valid")
+    }
+}
+
+impl Scenario {
+    pub fn load(path: &Path) -> Result<Self> {
+        let scenario: Scenario = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.players.is_empty() {
+            bail!("A scenario needs at least one player, the user.");
+        }
+        if self.steps.is_empty() {
+            bail!("A scenario needs at least one step.");
+        }
+
+        Ok(())
+    }
+
+    /// The user's own player.
+    pub fn user(&self) -> &ScenarioPlayer {
+        &self.players[0]
+    }
+
+    pub fn player(&self, puuid: &str) -> Option<&ScenarioPlayer> {
+        self.players.iter().find(|p| p.puuid == puuid)
+    }
+}
@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use crossterm::event::KeyCode;
+use haunt_core::{
+    lobby::{ShortMatchData, ShortPlayer},
+    login::LoginFail,
+    watcher::{Change, ClientState},
+    Client,
+};
+
+pub struct App {
+    pub client: Arc<Client>,
+    pub state: ClientState,
+    /// the current match, or the last one while in menus
+    pub lobby: Option<ShortMatchData>,
+    /// the stage login last failed at, until it succeeds
+    pub login_fail: Option<LoginFail>,
+    /// index into [`App::players`]
+    pub selected: usize,
+    /// whether the selected player's details are open
+    pub expanded: bool,
+    pub quit: bool,
+}
+
+/// A group of players drawn together, e.g. a team's column.
+pub struct Group<'a> {
+    pub title: String,
+    pub players: Vec<&'a ShortPlayer>,
+}
+
+/// Splits the lobby into teams, or a single group in free-for-all modes.
+/// Coaches and observers go in their own group at the end.
+pub fn groups(lobby: &ShortMatchData) -> Vec<Group> {
+    let mut groups = match lobby.team_based {
+        true => ["blue", "red"]
+            .into_iter()
+            .map(|team| Group {
+                title: format!("{} team", capitalise(team)),
+                players: lobby.players.iter().filter(|p| p.team == team).collect(),
+            })
+            .collect(),
+        false => vec![Group {
+            title: String::from("Players"),
+            players: lobby.players.iter().collect(),
+        }],
+    };
+
+    if !lobby.spectators.is_empty() {
+        groups.push(Group {
+            title: String::from("Spectators"),
+            players: lobby.spectators.iter().collect(),
+        });
+    }
+
+    groups
+}
+
+fn capitalise(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+impl App {
+    pub fn new(client: Arc<Client>) -> Self {
+        App {
+            client,
+            state: ClientState::Offline,
+            lobby: None,
+            login_fail: None,
+            selected: 0,
+            expanded: false,
+            quit: false,
+        }
+    }
+
+    /// Every player in the order they're drawn, so the selection moves down each column in turn.
+    pub fn players(&self) -> Vec<&ShortPlayer> {
+        self.lobby
+            .as_ref()
+            .map(|lobby| groups(lobby).into_iter().flat_map(|g| g.players).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn selected_player(&self) -> Option<&ShortPlayer> {
+        self.players().get(self.selected).copied()
+    }
+
+    pub fn apply(&mut self, changes: Vec<Change>) {
+        for change in changes {
+            match change {
+                Change::State { to, .. } => {
+                    if to != ClientState::Offline {
+                        self.login_fail = None;
+                    }
+                    self.state = to;
+                }
+                Change::LoginFailed(fail) => self.login_fail = Some(fail),
+                Change::Loaded(lobby) => {
+                    self.lobby = Some(lobby);
+                    self.selected = 0;
+                    self.expanded = false;
+                }
+                Change::Updated(lobby) => self.lobby = Some(lobby),
+            }
+        }
+
+        // teams can be reshuffled in custom games, so keep the selection in bounds
+        let count = self.players().len();
+        self.selected = self.selected.min(count.saturating_sub(1));
+    }
+
+    pub fn on_key(&mut self, key: KeyCode) {
+        let count = self.players().len();
+
+        match key {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Esc if self.expanded => self.expanded = false,
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') if self.selected + 1 < count => self.selected += 1,
+            KeyCode::Enter | KeyCode::Char(' ') if count > 0 => self.expanded = !self.expanded,
+            _ => (),
+        }
+    }
+}
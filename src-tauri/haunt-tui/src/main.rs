@@ -0,0 +1,162 @@
+use std::{fs::File, io, path::PathBuf, sync::Arc};
+
+use clap::Parser;
+use color_eyre::eyre::Result;
+use crossterm::{
+    event::{Event, EventStream, KeyEventKind},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use futures::StreamExt;
+use haunt_core::{
+    source,
+    streamer::StreamerMask,
+    watcher::{Change, LobbyWatcher},
+    Client,
+};
+use ratatui::{backend::CrosstermBackend, Terminal};
+use tokio::sync::mpsc;
+use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+
+mod app;
+mod ui;
+
+use app::App;
+
+/// Shows the current Valorant lobby full screen, updating as the match goes on.
+#[derive(Parser)]
+#[command(name = "haunt-tui", version)]
+struct Args {
+    /// Keep ValAPI content and name/MMR responses here between runs
+    #[arg(long, env = "HAUNT_CACHE_DIR")]
+    cache_dir: Option<PathBuf>,
+    /// Use the app's settings.json, for poll intervals, rank history and region overrides
+    #[arg(long, env = "HAUNT_SETTINGS")]
+    settings: Option<PathBuf>,
+    /// Write logs here. They'd draw over the UI if they went to the terminal
+    #[arg(long)]
+    log_file: Option<PathBuf>,
+    /// Show a made up lobby that loops through a match, instead of the running game
+    #[arg(long)]
+    demo: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    color_eyre::install()?;
+    let args = Args::parse();
+
+    if let Some(path) = &args.log_file {
+        tracing_subscriber::registry()
+            .with(
+                fmt::layer()
+                    .with_ansi(false)
+                    .with_writer(std::sync::Mutex::new(File::create(path)?)),
+            )
+            .with(EnvFilter::from_default_env())
+            .init();
+    }
+
+    // HAUNT_RECORD, for bug reports
+    haunt_core::traffic::from_env()?;
+
+    let client = Client::new()?;
+    // loaded first, since content is fetched in the language it picks
+    if let Some(path) = args.settings {
+        client.settings.load(path);
+    }
+    client.load_cache(args.cache_dir.as_deref()).await;
+    let client = Arc::new(client);
+
+    // --demo, then HAUNT_REPLAY or HAUNT_BACKEND=demo, then the data source setting
+    client
+        .source
+        .set(match (args.demo, source::Backend::from_env()) {
+            (true, _) => source::Backend::Demo,
+            (false, source::Backend::Live) => client.settings.get().data_source.clone(),
+            (false, backend) => backend,
+        })?;
+    let changes = spawn_watcher(client.clone());
+
+    // put the terminal back even if something panics, or the shell is left in raw mode
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        _ = restore_terminal();
+        hook(info);
+    }));
+
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+
+    let res = run(&mut terminal, App::new(client), changes).await;
+
+    restore_terminal()?;
+    terminal.show_cursor()?;
+
+    res
+}
+
+fn restore_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen)
+}
+
+/// Follows the client in the background, sending whatever changed after each poll. Lobbies are
+/// masked before they're sent if streamer mode is on.
+fn spawn_watcher(client: Arc<Client>) -> mpsc::UnboundedReceiver<Vec<Change>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        let mut watcher = LobbyWatcher::default();
+        let mask = StreamerMask::default();
+        let intervals = client.settings.get().poll_intervals.clone();
+
+        loop {
+            let mut changes = watcher.tick(&client).await;
+
+            let privacy = &client.settings.get().privacy;
+            if privacy.streamer_mode {
+                mask.set_user(watcher.user().map(str::to_string));
+                for change in &mut changes {
+                    if let Change::Loaded(lobby) | Change::Updated(lobby) = change {
+                        mask.lobby(lobby, privacy.hide_own_name, |_| ());
+                    }
+                }
+            }
+
+            if !changes.is_empty() && tx.send(changes).is_err() {
+                // the UI has closed
+                return;
+            }
+
+            tokio::time::sleep(intervals.for_state(watcher.state())).await;
+        }
+    });
+
+    rx
+}
+
+async fn run(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    mut app: App,
+    mut changes: mpsc::UnboundedReceiver<Vec<Change>>,
+) -> Result<()> {
+    let mut events = EventStream::new();
+
+    while !app.quit {
+        terminal.draw(|f| ui::draw(f, &app))?;
+
+        tokio::select! {
+            Some(changes) = changes.recv() => app.apply(changes),
+            Some(event) = events.next() => match event? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => app.on_key(key.code),
+                // anything else, e.g. a resize, just needs a redraw
+                _ => (),
+            },
+            else => break,
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+
+use haunt_core::{
+    lobby::{ShortMatchData, ShortPlayer},
+    pvp::matchdata::{FieldStatus, Role},
+    valapi::seasons::{self, Season},
+    watcher::ClientState,
+};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table},
+    Frame,
+};
+
+use crate::app::{self, App};
+
+/// Colours handed out to parties, in the order the parties are listed.
+const PARTY_COLOURS: [Color; 6] = [
+    Color::Magenta,
+    Color::Cyan,
+    Color::Yellow,
+    Color::Green,
+    Color::LightBlue,
+    Color::LightRed,
+];
+
+/// How many of the most recent acts get their own rank column.
+const RANK_COLUMNS: usize = 4;
+
+/// Gives each party with more than one player a colour. Players queueing alone get none.
+fn party_colours(lobby: &ShortMatchData) -> HashMap<String, Color> {
+    lobby
+        .parties()
+        .into_iter()
+        .map(|(party, number)| (party, PARTY_COLOURS[(number - 1) % PARTY_COLOURS.len()]))
+        .collect()
+}
+
+fn phase(state: ClientState) -> &'static str {
+    match state {
+        ClientState::Offline => "Offline",
+        ClientState::Menus => "In menus",
+        ClientState::Queue => "In queue",
+        ClientState::Pregame => "Agent select",
+        ClientState::Ingame => "In game",
+        ClientState::PostGame => "Match over",
+    }
+}
+
+pub fn draw(f: &mut Frame, app: &App) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(f.size());
+
+    draw_header(f, app, chunks[0]);
+
+    match &app.lobby {
+        Some(lobby) => draw_lobby(f, app, lobby, chunks[1]),
+        None => {
+            let waiting = match app.state {
+                ClientState::Offline => "Waiting for Valorant...",
+                _ => "Waiting for a match...",
+            };
+            f.render_widget(
+                Paragraph::new(waiting).style(Style::default().fg(Color::DarkGray)),
+                chunks[1],
+            );
+        }
+    }
+
+    let help = "↑/↓ select · enter details · esc close · q quit";
+    f.render_widget(
+        Paragraph::new(help).style(Style::default().fg(Color::DarkGray)),
+        chunks[2],
+    );
+
+    if app.expanded {
+        if let Some(player) = app.selected_player() {
+            draw_details(f, app, player);
+        }
+    }
+}
+
+fn draw_header(f: &mut Frame, app: &App, area: Rect) {
+    let mut spans = vec![
+        Span::styled("HAUNT", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw("  "),
+        Span::styled(phase(app.state), Style::default().fg(Color::Green)),
+    ];
+
+    if let Some(lobby) = &app.lobby {
+        let content = app.client.content.get();
+        let map = content
+            .maps
+            .iter()
+            .find(|m| m.map_url == lobby.map)
+            .map_or(lobby.map.clone(), |m| m.display_name.clone());
+
+        spans.push(Span::raw(format!("  ·  {map} · {}", lobby.mode)));
+        if !matches!(app.state, ClientState::Pregame | ClientState::Ingame) {
+            spans.push(Span::styled(
+                "  (last match)",
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+    }
+
+    if let Some(fail) = app.login_fail {
+        spans.push(Span::styled(
+            format!("  {fail}"),
+            Style::default().fg(Color::Red),
+        ));
+    }
+
+    f.render_widget(
+        Paragraph::new(Line::from(spans)).block(Block::default().borders(Borders::ALL)),
+        area,
+    );
+}
+
+fn draw_lobby(f: &mut Frame, app: &App, lobby: &ShortMatchData, area: Rect) {
+    let groups = app::groups(lobby);
+    let colours = party_colours(lobby);
+
+    // a player's history skips acts they didn't play, so the columns come from the content
+    let content = app.client.content.get();
+    let mut acts = seasons::past_acts(&content.seasons);
+    acts.truncate(RANK_COLUMNS);
+
+    // teams side by side, with spectators underneath
+    let teams = match lobby.spectators.is_empty() {
+        true => groups.len(),
+        false => groups.len() - 1,
+    };
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(match lobby.spectators.len() {
+            0 => vec![Constraint::Min(0)],
+            n => vec![Constraint::Min(0), Constraint::Length(n as u16 + 3)],
+        })
+        .split(area);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, teams as u32); teams])
+        .split(rows[0]);
+
+    // the selection counts through the groups in order, like `App::players`
+    let mut offset = 0;
+    let areas = columns.iter().chain(rows.get(1));
+    for (group, area) in groups.iter().zip(areas) {
+        draw_group(f, app, group, &colours, &acts, offset, *area);
+        offset += group.players.len();
+    }
+}
+
+fn draw_group(
+    f: &mut Frame,
+    app: &App,
+    group: &app::Group,
+    colours: &HashMap<String, Color>,
+    acts: &[&Season],
+    offset: usize,
+    area: Rect,
+) {
+    let mut header = ["", "Agent", "Name", "Lvl"].map(String::from).to_vec();
+    header.extend(acts.iter().map(|act| act.name()));
+
+    let rows = group.players.iter().enumerate().map(|(i, player)| {
+        let party = match colours.get(&player.party_id) {
+            Some(colour) => Span::styled("●", Style::default().fg(*colour)),
+            None => Span::raw(""),
+        };
+
+        let agent = match &player.character {
+            Some(agent) if player.locked => Span::styled(
+                agent.display_name.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Some(agent) => Span::styled(
+                agent.display_name.clone(),
+                Style::default()
+                    .fg(Color::DarkGray)
+                    .add_modifier(Modifier::ITALIC),
+            ),
+            None => Span::styled("-", Style::default().fg(Color::DarkGray)),
+        };
+
+        let mut cells = vec![
+            Cell::from(party),
+            Cell::from(agent),
+            Cell::from(player.name.clone()),
+            Cell::from(
+                player
+                    .account_level
+                    .map(|l| l.to_string())
+                    .unwrap_or_default(),
+            ),
+        ];
+        cells.extend(acts.iter().map(|act| {
+            let rank = player.rank_in(&act.season_uuid);
+            Cell::from(rank.map(|r| r.tier_name.clone()).unwrap_or_default())
+        }));
+
+        let style = match offset + i == app.selected {
+            true => Style::default().add_modifier(Modifier::REVERSED),
+            false => Style::default(),
+        };
+        Row::new(cells).style(style)
+    });
+
+    let mut widths = vec![
+        Constraint::Length(1),
+        Constraint::Length(10),
+        Constraint::Min(16),
+        Constraint::Length(4),
+    ];
+    widths.extend(vec![Constraint::Length(12); acts.len()]);
+
+    let table = Table::new(rows)
+        .header(Row::new(header).style(Style::default().add_modifier(Modifier::UNDERLINED)))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(group.title.clone()),
+        )
+        .widths(&widths)
+        .column_spacing(1);
+
+    f.render_widget(table, area);
+}
+
+fn status(status: &FieldStatus) -> String {
+    match status {
+        FieldStatus::Pending => String::from("pending"),
+        FieldStatus::Loaded => String::from("loaded"),
+        FieldStatus::Fallback => String::from("from presence"),
+        FieldStatus::Unavailable => String::from("unavailable"),
+        FieldStatus::Disabled => String::from("off in settings"),
+        FieldStatus::Error(why) => format!("failed: {why}"),
+    }
+}
+
+/// A popup with everything we know about the player.
+fn draw_details(f: &mut Frame, app: &App, player: &ShortPlayer) {
+    let area = f.size();
+    let area = Rect {
+        x: area.width / 6,
+        y: area.height / 6,
+        width: area.width * 2 / 3,
+        height: area.height * 2 / 3,
+    };
+
+    let label = |s: &'static str| Span::styled(s, Style::default().fg(Color::DarkGray));
+    let agent = match &player.character {
+        Some(agent) if player.locked => format!("{} (locked)", agent.display_name),
+        Some(agent) => format!("{} (hovering)", agent.display_name),
+        None => String::from("none yet"),
+    };
+    // players without a presence have no party ID, rather than all being in one party
+    let party_size = match player.party_id.is_empty() {
+        true => 1,
+        false => app
+            .lobby
+            .iter()
+            .flat_map(|l| l.participants())
+            .filter(|p| p.party_id == player.party_id)
+            .count(),
+    };
+    let role = match player.role {
+        Role::Player => "player",
+        Role::Coach => "coach",
+        Role::Observer => "observer",
+    };
+
+    let mut lines = vec![
+        Line::from(vec![label("Team      "), Span::raw(player.team.clone())]),
+        Line::from(vec![label("Role      "), Span::raw(role)]),
+        Line::from(vec![label("Agent     "), Span::raw(agent)]),
+        Line::from(vec![
+            label("Level     "),
+            Span::raw(
+                player
+                    .account_level
+                    .map_or(String::from("hidden"), |l| l.to_string()),
+            ),
+        ]),
+        Line::from(vec![
+            label("Party     "),
+            Span::raw(match party_size {
+                0 | 1 => String::from("solo"),
+                n => format!("{n} players"),
+            }),
+        ]),
+        Line::from(vec![label("UUID      "), Span::raw(player.uuid.clone())]),
+        Line::from(""),
+        Line::from(label("Rank history")),
+    ];
+
+    match player.rank_history.is_empty() {
+        true => lines.push(Line::from("  no competitive games in the last few acts")),
+        false => lines.extend(
+            player
+                .rank_history
+                .iter()
+                .map(|r| Line::from(format!("  {}", r.tier_name))),
+        ),
+    }
+
+    lines.extend([
+        Line::from(""),
+        Line::from(vec![
+            label("Name      "),
+            Span::raw(status(&player.status.name)),
+        ]),
+        Line::from(vec![
+            label("Ranks     "),
+            Span::raw(status(&player.status.rank_history)),
+        ]),
+    ]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(player.name.clone());
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
@@ -0,0 +1,44 @@
+//! Points ValAPI images at the asset protocol on their way to the webview, so they load from the
+//! image cache. Everything else, e.g. the content cache on disk and exports, keeps the ValAPI
+//! URLs, since the CLI and TUI can't load the protocol.
+
+use crate::api::{
+    commands::ShortMatchData, events::MatchEvent, friends::FriendStatus, valapi::images::rewrite,
+};
+
+pub fn lobby(mut lobby: ShortMatchData) -> ShortMatchData {
+    for player in lobby.participants_mut() {
+        if let Some(agent) = &mut player.character {
+            rewrite(&mut agent.display_icon);
+        }
+
+        for tier in &mut player.rank_history {
+            rewrite(&mut tier.icon);
+        }
+    }
+
+    lobby
+}
+
+pub fn event(event: MatchEvent) -> MatchEvent {
+    match event {
+        MatchEvent::MatchSkeleton(match_data) => MatchEvent::MatchSkeleton(lobby(match_data)),
+        MatchEvent::MatchLoaded(match_data) => MatchEvent::MatchLoaded(lobby(match_data)),
+        MatchEvent::MatchUpdated(match_data) => MatchEvent::MatchUpdated(lobby(match_data)),
+        MatchEvent::PlayerEnriched(mut update) => {
+            for tier in update.rank_history.iter_mut().flatten() {
+                rewrite(&mut tier.icon);
+            }
+            MatchEvent::PlayerEnriched(update)
+        }
+        event @ (MatchEvent::StateChanged { .. } | MatchEvent::AvoidAlert(_)) => event,
+    }
+}
+
+pub fn friends(mut friends: Vec<FriendStatus>) -> Vec<FriendStatus> {
+    for rank in friends.iter_mut().filter_map(|f| f.rank.as_mut()) {
+        rewrite(&mut rank.icon);
+    }
+
+    friends
+}
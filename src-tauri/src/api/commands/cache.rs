@@ -0,0 +1,20 @@
+use crate::api::pvp::cache::Endpoint;
+
+/// Drops cached name and mmr lookups so they're fetched again next match.
+///
+/// * `endpoint` - Only drop lookups from this endpoint. Drops everything if None.
+/// * `puuid` - Only drop lookups for this player.
+#[tauri::command]
+pub async fn invalidate_cache(
+    state: tauri::State<'_, crate::HauntState>,
+    endpoint: Option<Endpoint>,
+    puuid: Option<String>,
+) -> Result<(), ()> {
+    info!("Invalidating cached responses: {:?} {:?}", endpoint, puuid);
+
+    let cache = &state.0.client.response_cache;
+    cache.invalidate(endpoint, puuid.as_deref());
+    cache.flush().map_err(|why| {
+        warn!("Unable to save the response cache: {why}");
+    })
+}
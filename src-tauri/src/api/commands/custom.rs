@@ -0,0 +1,163 @@
+use serde::Serialize;
+
+use crate::api::{
+    self, asset_urls,
+    events::{self, MatchEvent},
+    lobby::{self, LoadState},
+    local::{entitlements, sessions},
+    pvp::{
+        matchdata::MatchData,
+        party::{CustomGameTeam, Party},
+    },
+    streamer,
+};
+
+use super::games::{load_configs, ShortMatchData};
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomGameData {
+    pub party_id: String,
+    pub cheats: bool,
+    pub tournament_mode: bool,
+    pub use_bots: bool,
+    /// players are placed on blue (team one) and red (team two); coaches and observers are spectators
+    pub lobby: ShortMatchData,
+}
+
+async fn find_custom_game(
+    state: &tauri::State<'_, crate::HauntState>,
+    entitlements_config: &entitlements::Config,
+    session_config: &sessions::Config,
+) -> Result<Party, bool> {
+    let party = api::pvp::party::find_custom_game(
+        entitlements_config,
+        &state.0.client.http,
+        &state.0.client.rate_limiter,
+        session_config,
+    )
+    .await;
+
+    match party {
+        Some(party) => Ok(party),
+        None => {
+            info!("Player is not in a custom game lobby.");
+            Err(true)
+        }
+    }
+}
+
+/// Loads the current custom game lobby, if the user's party is setting one up.
+/// Err(false) means the user isn't logged in, Err(true) means there's no custom lobby.
+#[tauri::command]
+pub async fn load_custom_game(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::HauntState>,
+) -> Result<CustomGameData, bool> {
+    let (lockfile_config, entitlements_config, session_config) = load_configs(&state).await?;
+
+    let party = find_custom_game(&state, &entitlements_config, &session_config).await?;
+    let settings = &party.custom_game_data.settings;
+
+    // only used to fill in gaps if the name or mmr services fail
+    let presences =
+        api::local::presence::get_presences(&lockfile_config, &state.0.client.offline_http)
+            .await
+            .unwrap_or_default();
+
+    let mut match_data = MatchData::from(&party);
+    lobby::fill_player_info(
+        &state.0.client,
+        &mut match_data,
+        &presences,
+        &entitlements_config,
+        &session_config,
+        |update| events::emit(&app, MatchEvent::PlayerEnriched(update)),
+    )
+    .await;
+
+    let content = state.0.client.content.get();
+    let lobby = ShortMatchData::from_match_data(
+        &match_data,
+        &content.agents,
+        &content.competitive_tiers,
+        LoadState::Loaded,
+    );
+
+    Ok(CustomGameData {
+        party_id: party.id.clone(),
+        cheats: settings.cheats(),
+        tournament_mode: settings.tournament_mode(),
+        use_bots: settings.use_bots,
+        lobby: asset_urls::lobby(streamer::lobby(&state, lobby)),
+    })
+}
+
+/// Changes the map and/or mode of the current custom game. Only works for the party owner.
+///
+/// * `map` - The map path, e.g. `/Game/Maps/Ascent/Ascent`.
+/// * `mode` - The mode path, e.g. `/Game/GameModes/Bomb/BombGameMode.BombGameMode_C`.
+#[tauri::command]
+pub async fn set_custom_game_settings(
+    state: tauri::State<'_, crate::HauntState>,
+    map: Option<String>,
+    mode: Option<String>,
+) -> Result<(), bool> {
+    let (_, entitlements_config, session_config) = load_configs(&state).await?;
+
+    let party = find_custom_game(&state, &entitlements_config, &session_config).await?;
+
+    let mut settings = party.custom_game_data.settings.clone();
+    if let Some(map) = map {
+        settings.map = map;
+    }
+    if let Some(mode) = mode {
+        settings.mode = mode;
+    }
+
+    let res = api::pvp::party::set_custom_game_settings(
+        &party.id,
+        &settings,
+        &entitlements_config,
+        &state.0.client.http,
+        &state.0.client.rate_limiter,
+        &session_config,
+    )
+    .await;
+
+    res.map_err(|why| {
+        error!("Unable to update custom game settings: {why}");
+        true
+    })
+}
+
+/// Moves a player in the current custom game to another team.
+///
+/// * `puuid` - The player to move.
+/// * `team` - The team to move them to.
+#[tauri::command]
+pub async fn move_custom_game_player(
+    state: tauri::State<'_, crate::HauntState>,
+    puuid: String,
+    team: CustomGameTeam,
+) -> Result<(), bool> {
+    let (_, entitlements_config, session_config) = load_configs(&state).await?;
+
+    let party = find_custom_game(&state, &entitlements_config, &session_config).await?;
+
+    let res = api::pvp::party::move_to_team(
+        &party.id,
+        &puuid,
+        team,
+        &entitlements_config,
+        &state.0.client.http,
+        &state.0.client.rate_limiter,
+        &session_config,
+    )
+    .await;
+
+    res.map_err(|why| {
+        error!("Unable to move player {puuid}: {why}");
+        true
+    })
+}
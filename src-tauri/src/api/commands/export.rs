@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::{
+    pvp::matchdata::Role,
+    streamer,
+    valapi::{content::Content, seasons},
+};
+
+use super::games::{ShortMatchData, ShortPlayer};
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Markdown,
+}
+
+/// What gets exported for each player. Names and account levels are taken from [`ShortPlayer`],
+/// so incognito and hidden-level players stay masked, as does everyone masked by streamer mode.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedPlayer {
+    /// None in free-for-all modes, where every player is on their own team
+    team: Option<String>,
+    name: String,
+    agent: Option<String>,
+    role: Role,
+    account_level: Option<u32>,
+    /// players in the same party share a number. None for players queueing alone
+    party: Option<usize>,
+    /// most recent act first. acts the player didn't play are skipped
+    ranks: Vec<ExportedRank>,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportedRank {
+    /// act name, e.g. `E7 A2`
+    act: String,
+    rank: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportedLobby {
+    match_id: String,
+    map: String,
+    mode: String,
+    /// every act anyone has a rank in, most recent first. CSV and Markdown get a column for each
+    #[serde(skip)]
+    acts: Vec<String>,
+    players: Vec<ExportedPlayer>,
+}
+
+impl ExportedLobby {
+    fn new(match_data: &ShortMatchData, content: &Content) -> Self {
+        let participants = match_data.participants().collect::<Vec<_>>();
+
+        // party ids are meaningless to anyone reading the export, so number the parties instead
+        let parties = match_data.parties();
+
+        // one player's history can skip acts another's has, so the order comes from the content
+        let past_acts = seasons::past_acts(&content.seasons);
+        let mut acts = Vec::<&str>::new();
+        for rank in participants.iter().flat_map(|p| &p.rank_history) {
+            if !acts.contains(&rank.act.as_str()) {
+                acts.push(&rank.act);
+            }
+        }
+        acts.sort_by_key(|act| {
+            past_acts
+                .iter()
+                .position(|s| s.season_uuid == *act)
+                .unwrap_or(usize::MAX)
+        });
+
+        let map = content
+            .maps
+            .iter()
+            .find(|m| m.map_url == match_data.map)
+            .map_or_else(|| match_data.map.clone(), |m| m.display_name.clone());
+
+        ExportedLobby {
+            match_id: match_data.match_id.clone(),
+            map,
+            mode: match_data.mode.clone(),
+            acts: acts
+                .iter()
+                .map(|act| seasons::act_name(&content.seasons, act))
+                .collect(),
+            players: participants
+                .iter()
+                .map(|player| ExportedPlayer::new(player, match_data.team_based, &parties, content))
+                .collect(),
+        }
+    }
+
+    /// Column headers, followed by the name of each act.
+    fn headers(&self) -> Vec<String> {
+        HEADERS
+            .iter()
+            .map(|h| h.to_string())
+            .chain(self.acts.iter().cloned())
+            .collect()
+    }
+}
+
+impl ExportedPlayer {
+    fn new(
+        player: &ShortPlayer,
+        team_based: bool,
+        parties: &HashMap<String, usize>,
+        content: &Content,
+    ) -> Self {
+        ExportedPlayer {
+            team: team_based.then(|| player.team.clone()),
+            name: player.name.clone(),
+            agent: player.character.as_ref().map(|a| a.display_name.clone()),
+            role: player.role,
+            account_level: player.account_level,
+            party: parties.get(&player.party_id).copied(),
+            ranks: player
+                .rank_history
+                .iter()
+                .map(|r| ExportedRank {
+                    act: seasons::act_name(&content.seasons, &r.act),
+                    rank: r.tier_name.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// The player's row, with a rank column for each of `acts`.
+    fn columns(&self, acts: &[String]) -> Vec<String> {
+        let ranks = acts.iter().map(|act| {
+            self.ranks
+                .iter()
+                .find(|r| &r.act == act)
+                .map(|r| r.rank.clone())
+                .unwrap_or_default()
+        });
+
+        [
+            self.team.clone().unwrap_or_default(),
+            self.name.clone(),
+            self.agent.clone().unwrap_or_default(),
+            format!("{:?}", self.role).to_lowercase(),
+            self.account_level
+                .map(|l| l.to_string())
+                .unwrap_or_default(),
+            self.party.map(|p| p.to_string()).unwrap_or_default(),
+        ]
+        .into_iter()
+        .chain(ranks)
+        .collect()
+    }
+}
+
+const HEADERS: [&str; 6] = ["Team", "Name", "Agent", "Role", "Level", "Party"];
+
+fn csv_field(field: &str) -> String {
+    match field.contains([',', '"', '\n']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+fn to_csv(lobby: &ExportedLobby) -> String {
+    let row = |columns: Vec<String>| {
+        columns
+            .iter()
+            .map(|c| csv_field(c))
+            .collect::<Vec<_>>()
+            .join(",")
+            + "\n"
+    };
+
+    let mut csv = row(lobby.headers());
+    for player in &lobby.players {
+        csv += &row(player.columns(&lobby.acts));
+    }
+
+    csv
+}
+
+fn markdown_field(field: &str) -> String {
+    field.replace('|', "\\|")
+}
+
+fn to_markdown(lobby: &ExportedLobby) -> String {
+    let row = |columns: Vec<String>| {
+        let columns = columns
+            .iter()
+            .map(|c| markdown_field(c))
+            .collect::<Vec<_>>();
+        format!("| {} |\n", columns.join(" | "))
+    };
+
+    let headers = lobby.headers();
+    let separator = "---|".repeat(headers.len());
+
+    let mut md = format!("**{}** - {}\n\n", lobby.map, lobby.mode);
+    md += &row(headers);
+    md += &format!("|{separator}\n");
+    for player in &lobby.players {
+        md += &row(player.columns(&lobby.acts));
+    }
+
+    md
+}
+
+/// Exports the current lobby. Err(true) if there's no match loaded or the file can't be written.
+///
+/// * `format` - The format to export to.
+/// * `path` - Where to write the export. The text is returned either way, e.g. for the clipboard.
+#[tauri::command]
+pub async fn export_lobby(
+    state: tauri::State<'_, crate::HauntState>,
+    format: ExportFormat,
+    path: Option<String>,
+) -> Result<String, bool> {
+    let Some(match_data) = state.0.match_cache.lock().await.clone() else {
+        info!("No match loaded to export.");
+        return Err(true);
+    };
+
+    let match_data = streamer::lobby(&state, match_data);
+    let lobby = ExportedLobby::new(&match_data, &state.0.client.content.get());
+    let text = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(&lobby).map_err(|why| {
+            error!("Unable to serialize lobby: {why}");
+            true
+        })?,
+        ExportFormat::Csv => to_csv(&lobby),
+        ExportFormat::Markdown => to_markdown(&lobby),
+    };
+
+    if let Some(path) = path {
+        std::fs::write(&path, &text).map_err(|why| {
+            error!("Unable to write lobby export to {path}: {why}");
+            true
+        })?;
+        info!("Exported lobby to {path}.");
+    }
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lobby(name: &str) -> ExportedLobby {
+        ExportedLobby {
+            match_id: String::from("match"),
+            map: String::from("Ascent"),
+            mode: String::from("Competitive"),
+            acts: vec![String::from("E7 A2"), String::from("E7 A1")],
+            players: vec![ExportedPlayer {
+                team: Some(String::from("blue")),
+                name: name.to_string(),
+                agent: Some(String::from("Jett")),
+                role: Role::Player,
+                account_level: Some(120),
+                party: None,
+                ranks: vec![ExportedRank {
+                    act: String::from("E7 A1"),
+                    rank: String::from("Gold 2"),
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        assert_eq!(csv_field("Jett"), "Jett");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn csv_has_a_column_per_act() {
+        let csv = to_csv(&lobby("pluh, playa #boulets"));
+
+        assert_eq!(
+            csv,
+            "Team,Name,Agent,Role,Level,Party,E7 A2,E7 A1\n\
+             blue,\"pluh, playa #boulets\",Jett,player,120,,,Gold 2\n"
+        );
+    }
+
+    #[test]
+    fn markdown_escapes_pipes() {
+        let md = to_markdown(&lobby("a|b #c"));
+
+        assert!(md.contains("| blue | a\\|b #c | Jett |"));
+        // the escaped pipe doesn't split the name into another column
+        assert_eq!(md.lines().last().unwrap().matches(" | ").count(), 7);
+    }
+}
@@ -1,14 +1,21 @@
+use std::collections::HashMap;
+
 use serde::Serialize;
 
 use crate::api::{
-    self,
+    self, asset_urls,
+    encounters::{self, EncounterSummary, NameRecord},
+    events::{self, MatchEvent},
+    lobby::{self, LoadState},
     local::{entitlements, sessions, presence},
     lockfile,
-    pvp::matchdata::{MatchData, Player},
-    valapi::{agents::Agent, seasons::CompetitiveTier},
+    notes::{self, PlayerNote},
+    source::Backend,
+    streamer,
+    valapi::seasons,
 };
 
-async fn load_configs(
+pub(crate) async fn load_configs(
     state: &tauri::State<'_, crate::HauntState>,
 ) -> Result<(lockfile::Config, entitlements::Config, sessions::Config), bool> {
     let lockfile_config = state.0.lockfile_config.lock().await;
@@ -44,8 +51,8 @@ async fn refresh_login(
     session_config: &sessions::Config,
 ) -> Result<(String, Vec<presence::Player>), bool> {
     info!("Ensuring correct user still logged in...");
-    let players =
-        api::local::presence::get_presences(&lockfile_config, &state.0.offline_http).await;
+    let client = &state.0.client;
+    let players = client.source.get().presences(client, lockfile_config).await;
 
     let Ok(players) = players else {
         error!("Unable to load player presences.");
@@ -66,8 +73,11 @@ async fn refresh_login(
     info!("Checking if player is in a match...");
 
     // we don't actually need the match id but it's a quick way to check ingame status
-    let match_id =
-        api::pvp::find_match_id(&entitlements_config, &state.0.http, &session_config).await;
+    let match_id = client
+        .source
+        .get()
+        .find_match(client, entitlements_config, session_config)
+        .await;
     let match_id = match match_id {
         Some(id) => {
             info!("Player is in a match.");
@@ -83,49 +93,28 @@ async fn refresh_login(
     Ok((match_id, players))
 }
 
-#[tauri::command]
-pub async fn load_match(
-    state: tauri::State<'_, crate::HauntState>,
+/// Loads the match from scratch, fills in every player and caches the result.
+/// The match is emitted as a [`MatchEvent::MatchSkeleton`] before any player data is loaded.
+pub(crate) async fn load_match_data(
+    app: &tauri::AppHandle,
+    state: &tauri::State<'_, crate::HauntState>,
+    entitlements_config: &entitlements::Config,
+    session_config: &sessions::Config,
+    match_id: &str,
+    players: &[presence::Player],
 ) -> Result<ShortMatchData, bool> {
-    let (lockfile_config, entitlements_config, session_config) = load_configs(&state).await?;
-
-    let (match_id, players) = refresh_login(
-        &state,
-        &lockfile_config,
-        &entitlements_config,
-        &session_config,
-    )
-    .await?;
-
-    let match_info = api::local::presence::get_match_info(&session_config.puuid, &players).await;
-    debug!("Match info: {:#?}", match_info);
-
-    info!("Loading player info.");
-    api::player::debug_parties(&players);
-
-    let seasons = api::valapi::seasons::get_prev_3_seasons(&state).await;
-    let seasons = match &seasons {
-        // we want the last 3 seasons plus the current one
-        Ok(seasons) => &seasons[..=3],
-        Err(why) => {
-            error!("Unable to load seasons: {:#?}", why);
-            return Err(true);
-        }
-    };
-
-    debug!(
-        "Past 3 seasons: {:#?}",
-        seasons.iter().map(|s| &s.season_uuid).collect::<Vec<_>>()
-    );
-
-    let match_data = api::pvp::matchdata::get_match_info(
-        &session_config,
-        &entitlements_config,
-        &match_id,
-        &players,
-        &state.0.http,
-    )
-    .await;
+    let client = &state.0.client;
+    let match_data = client
+        .source
+        .get()
+        .match_info(
+            client,
+            entitlements_config,
+            session_config,
+            match_id,
+            players,
+        )
+        .await;
     let mut match_data = match match_data {
         Ok(match_data) => match_data,
         Err(why) => {
@@ -134,57 +123,88 @@ pub async fn load_match(
         }
     };
 
-    let names = api::pvp::names::load_player_names(
-        &match_data.players,
-        &session_config,
-        &entitlements_config,
-        &state.0.http,
+    // prefetched list of agents, mapped to uuid
+    let content = state.0.client.content.get();
+    let agents = &content.agents;
+    let tiers = &content.competitive_tiers;
+
+    let settings = state.0.client.settings.get();
+    // recordings and demo lobbies are kept out of the user's own history
+    let record_encounters =
+        settings.privacy.record_encounters && client.source.backend() == Backend::Live;
+
+    // map, mode, teams and agents are all known at this point, so the UI can draw the table
+    let mut skeleton =
+        ShortMatchData::from_match_data(&match_data, agents, tiers, LoadState::Loading);
+    if settings.enrichments.encounters {
+        encounters::attach_summaries(&state.0.encounters, &mut skeleton, &session_config.puuid);
+    }
+    notes::attach_notes(&state.0.notes, &mut skeleton);
+    *state.0.match_cache.lock().await = Some(skeleton.clone());
+    events::emit(app, MatchEvent::MatchSkeleton(skeleton));
+
+    lobby::fill_player_info(
+        &state.0.client,
+        &mut match_data,
+        players,
+        entitlements_config,
+        session_config,
+        |update| events::emit(app, MatchEvent::PlayerEnriched(update)),
     )
     .await;
-    let Ok(names) = names else {
-        error!("Unable to load player names.");
-        return Err(true);
-    };
-    for name in names {
-        let player = match_data
-            .players
-            .iter_mut()
-            .find(|p| p.puuid == name.0)
-            .unwrap(); // player exists because we got it from this Vec
-
-        player.set_name(name.1);
-    }
 
-    debug!("Filling match history with acts: {:#?}", seasons);
-
-    for player in &mut match_data.players {
-        info!("Filling history for player {}", player.puuid);
-        let history = api::pvp::mmr::get_player_history(
-            &player.puuid,
-            &state.0.http,
-            &entitlements_config,
-            &session_config,
-            seasons,
-        )
-        .await;
-        let history = match history {
-            Ok(history) => history,
-            Err(why) => {
-                warn!(
-                    "Unable to load player history: {:#?}. Using empty Vec.",
-                    why
-                );
-                Vec::new()
+    // incognito players are left out so their names can't leak through their history.
+    // names are part of the encounter history, so nothing is recorded if that's turned off
+    let mut previous_names = match_data
+        .participants()
+        .filter(|p| record_encounters && !p.is_incognito())
+        .filter_map(|p| {
+            let name = Some(p.get_name(agents)).filter(|_| encounters::name_known(&p.status.name));
+            match state
+                .0
+                .encounters
+                .record_name(&p.puuid, name.as_deref(), match_id)
+            {
+                Ok(previous) => previous.map(|previous| (p.puuid.clone(), previous)),
+                Err(why) => {
+                    debug!("Unable to record name for {}: {why}", p.puuid);
+                    None
+                }
             }
-        };
-        player.competitive_history = history;
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut short_match =
+        ShortMatchData::from_match_data(&match_data, agents, tiers, LoadState::Loaded);
+    if settings.enrichments.encounters {
+        for player in short_match.participants_mut() {
+            player.extra.previous_name = previous_names.remove(&player.uuid);
+        }
+        encounters::attach_summaries(&state.0.encounters, &mut short_match, &session_config.puuid);
     }
+    notes::attach_notes(&state.0.notes, &mut short_match);
 
-    // prefetched list of agents, mapped to uuid
-    let agents = &state.0.agents;
-    let tiers = &state.0.competitive_tiers;
+    // sent now rather than with the skeleton, so they carry the player's name
+    for alert in notes::avoid_alerts(&short_match) {
+        info!("Avoid-listed player {} is in this match.", alert.note.puuid);
+        events::emit(app, MatchEvent::AvoidAlert(alert));
+    }
 
-    let short_match = ShortMatchData::from_match_data(match_data, agents, tiers);
+    if record_encounters {
+        let incognito = match_data
+            .participants()
+            .filter(|p| p.is_incognito())
+            .map(|p| p.puuid.clone())
+            .collect();
+        let current_act = seasons::current_act(&content.seasons).map(|s| s.season_uuid.as_str());
+        let recorded = state
+            .0
+            .encounters
+            .record_match(&short_match, &incognito, current_act);
+        if let Err(why) = recorded {
+            warn!("Unable to record match {match_id}: {why}");
+        }
+    }
 
     let mut match_cache = state.0.match_cache.lock().await;
     *match_cache = Some(short_match.clone());
@@ -192,138 +212,116 @@ pub async fn load_match(
     Ok(short_match)
 }
 
-#[tauri::command]
-pub async fn quick_update_match(
-    state: tauri::State<'_, crate::HauntState>,
+/// Refetches agent selection for the cached match. Err(true) if the cache is missing or stale.
+pub(crate) async fn quick_update_match_data(
+    state: &tauri::State<'_, crate::HauntState>,
+    entitlements_config: &entitlements::Config,
+    session_config: &sessions::Config,
+    match_id: &str,
+    players: &[presence::Player],
 ) -> Result<ShortMatchData, bool> {
-    let (lockfile_config, entitlements_config, session_config) = load_configs(&state).await?;
-
-    let (match_id, players) = refresh_login(
-        &state,
-        &lockfile_config,
-        &entitlements_config,
-        &session_config,
-    ).await?;
-
     let match_cache = &state.0.match_cache;
     let mut match_cache = match_cache.lock().await;
 
     // if we don't have a match cache, or it's from a stale match, throw the user back to pregame 
     // &mut *match_cache is actually just fucked up beyond human comprehension
     let match_cache = match &mut *match_cache {
-        Some(match_cache) => match_cache,
-        None => {
+        Some(match_cache) if match_cache.match_id == match_id => match_cache,
+        _ => {
             info!("Invalid match cache. Returning to pregame.");
             return Err(true);
         }
     };
+    
+    // player names won't change here. we're just refetching agent status, that's it
+    let updated = lobby::quick_update(
+        &state.0.client,
+        match_cache,
+        entitlements_config,
+        session_config,
+        players,
+    )
+    .await;
+    if let Err(why) = updated {
+        error!("Unable to load match players: {:#?}", why);
+        return Err(true);
+    }
+
+    Ok(match_cache.clone())
+}
+
+#[tauri::command]
+pub async fn load_match(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::HauntState>,
+) -> Result<ShortMatchData, bool> {
+    let (lockfile_config, entitlements_config, session_config) = load_configs(&state).await?;
+
+    let (match_id, players) = refresh_login(
+        &state,
+        &lockfile_config,
+        &entitlements_config,
+        &session_config,
+    )
+    .await?;
 
     let match_info = api::local::presence::get_match_info(&session_config.puuid, &players).await;
     debug!("Match info: {:#?}", match_info);
 
-    let match_data = api::pvp::matchdata::get_match_info(
-        &session_config,
+    info!("Loading player info.");
+    api::player::debug_parties(&players);
+
+    load_match_data(
+        &app,
+        &state,
         &entitlements_config,
+        &session_config,
         &match_id,
         &players,
-        &state.0.http,
     )
-    .await;
-    let match_data = match match_data {
-        Ok(match_data) => match_data,
-        Err(why) => {
-            error!("Unable to load match players: {:#?}", why);
-            return Err(true);
-        }
-    };
-    
-    // player names won't change here. we're just refetching agent status, that's it
+    .await
+    .map(|match_data| asset_urls::lobby(streamer::lobby(&state, match_data)))
+}
 
-    // prefetched list of agents, mapped to uuid
-    let agents = &state.0.agents;
+#[tauri::command]
+pub async fn quick_update_match(
+    state: tauri::State<'_, crate::HauntState>,
+) -> Result<ShortMatchData, bool> {
+    let (lockfile_config, entitlements_config, session_config) = load_configs(&state).await?;
 
-    match_cache.update_with_match_data(match_data, agents);
+    let (match_id, players) = refresh_login(
+        &state,
+        &lockfile_config,
+        &entitlements_config,
+        &session_config,
+    ).await?;
 
-    Ok(match_cache.clone())
-}
+    let match_info = api::local::presence::get_match_info(&session_config.puuid, &players).await;
+    debug!("Match info: {:#?}", match_info);
 
-#[derive(Clone, Debug, Serialize)]
-pub struct ShortMatchData {
-    pub ingame: bool,
-    pub map: String,
-    pub mode: String,
-    pub players: Vec<ShortPlayer>,
+    quick_update_match_data(
+        &state,
+        &entitlements_config,
+        &session_config,
+        &match_id,
+        &players,
+    )
+    .await
+    .map(|match_data| asset_urls::lobby(streamer::lobby(&state, match_data)))
 }
 
-#[derive(Clone, Debug, Serialize)]
-pub struct ShortPlayer {
-    pub uuid: String,
-    pub name: String,
-    pub team: String,
+/// What the app attaches to each player on top of the lobby data.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PlayerExtras {
+    /// previous matches with this player. None if there aren't any, or for the user themselves
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub character: Option<Agent>,
-    pub title: String,
-    #[serde(rename = "accountLevel", skip_serializing_if = "Option::is_none")]
-    pub account_level: Option<u32>,
-    #[serde(rename = "rankHistory")]
-    pub rank_history: Vec<CompetitiveTier>,
-    #[serde(rename = "partyId")]
-    pub party_id: String,
-}
-
-impl ShortPlayer {
-    fn from_player(value: &Player, agents: &Vec<Agent>, tiers: &Vec<CompetitiveTier>) -> Self {
-        println!("{:#?}", value);
-        ShortPlayer {
-            uuid: value.puuid.clone(),
-            name: value.get_name(agents),
-            team: value.team.to_string(),
-            character: value.get_agent(agents),
-            title: value.title.clone(),
-            account_level: value.get_account_level(),
-            rank_history: value
-                .competitive_history
-                .iter()
-                .map(|a| CompetitiveTier::from_act_tier(tiers, &a.episode_id, a.competitive_tier))
-                .collect(),
-            party_id: value.party_id.clone(),
-        }
-    }
+    pub encounters: Option<EncounterSummary>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<PlayerNote>,
+    /// the player's Riot ID when we last saw them, if they've changed it since
+    #[serde(rename = "previousName", skip_serializing_if = "Option::is_none")]
+    pub previous_name: Option<NameRecord>,
 }
 
-impl ShortMatchData {
-    fn from_match_data(
-        value: MatchData,
-        agents: &Vec<Agent>,
-        tiers: &Vec<CompetitiveTier>,
-    ) -> Self {
-        ShortMatchData {
-            ingame: value.ingame,
-            map: value.map,
-            mode: value.mode,
-            players: value
-                .players
-                .iter()
-                .map(|p| ShortPlayer::from_player(p, agents, tiers))
-                .collect(),
-        }
-    }
-
-    fn update_with_match_data(
-        &mut self,
-        value: MatchData,
-        agents: &Vec<Agent>,
-    ) {
-        self.ingame = value.ingame;
-        
-        for player in &mut self.players {
-            let updated_player = value.players.iter().find(|p| p.puuid == player.uuid);
-            let Some(updated_player) = updated_player else {
-                continue;
-            };
-
-            player.character = updated_player.get_agent(agents);
-            player.party_id = updated_player.party_id.clone();
-        }
-    }
-}
+pub type ShortMatchData = lobby::ShortMatchData<PlayerExtras>;
+pub type ShortPlayer = lobby::ShortPlayer<PlayerExtras>;
@@ -1,5 +1,17 @@
+mod cache;
+mod custom;
+mod export;
 mod games;
 mod login;
+mod recording;
+mod source;
 
+pub use cache::*;
+pub use custom::*;
+pub use export::*;
 pub use games::*;
 pub use login::*;
+pub use recording::*;
+pub use source::*;
+
+pub(crate) use games::{load_configs, load_match_data, quick_update_match_data};
@@ -0,0 +1,24 @@
+use tauri::Manager;
+
+use crate::api::traffic;
+
+/// Starts recording local and pd/glz traffic for a bug report. Names, PUUIDs and tokens are
+/// redacted as it's written. Returns the recording's path.
+#[tauri::command]
+pub fn start_recording(app: tauri::AppHandle) -> Result<String, ()> {
+    let dir = app
+        .path()
+        .app_local_data_dir()
+        .map_err(|why| warn!("No app data directory to record to: {why}"))?
+        .join("recordings");
+
+    traffic::start_recording(&dir)
+        .map(|path| path.display().to_string())
+        .map_err(|why| warn!("Unable to start recording: {why}"))
+}
+
+/// Stops recording. Returns the recording's path, or None if nothing was being recorded.
+#[tauri::command]
+pub fn stop_recording() -> Option<String> {
+    traffic::stop_recording().map(|path| path.display().to_string())
+}
@@ -0,0 +1,28 @@
+use crate::api::{
+    settings::{self, SettingsError},
+    source::Backend,
+};
+
+/// Returns where lobbies are currently loaded from.
+#[tauri::command]
+pub fn get_data_source(state: tauri::State<'_, crate::HauntState>) -> Backend {
+    state.0.client.source.backend()
+}
+
+/// Switches where lobbies are loaded from and saves it as the `dataSource` setting.
+/// The frontend has to log in again afterwards.
+#[tauri::command]
+pub async fn set_data_source(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, crate::HauntState>,
+    backend: Backend,
+) -> Result<(), SettingsError> {
+    settings::update(&app, |settings| settings.data_source = backend.clone()).await?;
+
+    // the setting may have matched already while HAUNT_REPLAY or HAUNT_BACKEND overrode it
+    if state.0.client.source.backend() != backend {
+        settings::switch_source(&state, backend).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use std::{path::PathBuf, time::Duration};
+
+use color_eyre::eyre::Result;
+use tauri::{AppHandle, Manager};
+
+use crate::api::events;
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(5);
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// Directory the ValAPI content cache is written to.
+pub fn content_dir(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_local_data_dir()
+        .ok()
+        .map(|dir| dir.join("ValAPI"))
+}
+
+/// Refetches ValAPI content if it's out of date, then emits the content ready event.
+async fn refresh(app: &AppHandle) -> Result<()> {
+    let state = app.state::<crate::HauntState>();
+
+    let dir = content_dir(app);
+    if dir.is_none() {
+        warn!("No app data directory. ValAPI content won't be cached.");
+    }
+
+    let version = state.0.client.refresh_content(dir.as_deref()).await?;
+    events::emit_content_ready(app, &version.version);
+
+    Ok(())
+}
+
+/// Runs [`refresh`] in the background, retrying with backoff until it succeeds.
+pub fn spawn_refresh(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut backoff = INITIAL_BACKOFF;
+
+        loop {
+            match refresh(&app).await {
+                Ok(_) => break,
+                Err(why) => {
+                    warn!("Unable to refresh ValAPI content, retrying in {backoff:?}: {why}");
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+            }
+        }
+    });
+}
+
+/// Refreshes the ValAPI content cache now, instead of waiting for the next launch.
+#[tauri::command]
+pub async fn update_files(app_handle: tauri::AppHandle) -> Result<(), ()> {
+    refresh(&app_handle).await.map_err(|why| {
+        warn!("Unable to update ValAPI content: {why}");
+    })
+}
+
+/// Whether ValAPI content has loaded. Until it has, agents and ranks fall back to UUIDs.
+#[tauri::command]
+pub async fn is_content_ready(state: tauri::State<'_, crate::HauntState>) -> Result<bool, ()> {
+    Ok(!state.0.client.content.get().is_empty())
+}
@@ -0,0 +1,37 @@
+use std::{path::Path, sync::Mutex};
+
+use color_eyre::eyre::{eyre, Result};
+use rusqlite::Connection;
+
+/// A local SQLite database that's opened once the app data directory is known.
+/// Every query fails until [`Database::open`] succeeds.
+#[derive(Debug, Default)]
+pub struct Database {
+    conn: Mutex<Option<Connection>>,
+}
+
+impl Database {
+    /// Opens (or creates) the database and makes sure the schema exists.
+    ///
+    /// * `schema` - `CREATE ... IF NOT EXISTS` statements, run on every open.
+    pub fn open(&self, path: &Path, schema: &str) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let conn = Connection::open(path)?;
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        conn.execute_batch(schema)?;
+
+        *self.conn.lock().unwrap() = Some(conn);
+
+        Ok(())
+    }
+
+    pub fn with_conn<T>(&self, f: impl FnOnce(&mut Connection) -> Result<T>) -> Result<T> {
+        let mut conn = self.conn.lock().unwrap();
+        let conn = conn.as_mut().ok_or_else(|| eyre!("Database isn't open."))?;
+
+        f(conn)
+    }
+}
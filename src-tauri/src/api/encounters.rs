@@ -0,0 +1,512 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use chrono::{DateTime, TimeZone, Utc};
+use color_eyre::eyre::Result;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+
+use haunt_core::pvp::matchdata::FieldStatus;
+
+use crate::api::{commands::ShortMatchData, db::Database, streamer};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS matches (
+    match_id TEXT PRIMARY KEY,
+    map      TEXT NOT NULL,
+    mode     TEXT NOT NULL,
+    -- unix timestamp, in seconds
+    seen_at  INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS encounters (
+    match_id TEXT NOT NULL REFERENCES matches (match_id) ON DELETE CASCADE,
+    puuid    TEXT NOT NULL,
+    -- null for incognito players, whose real name is hidden, and if it couldn't be loaded
+    name     TEXT,
+    team     TEXT NOT NULL,
+    agent    TEXT,
+    rank     TEXT,
+    party_id TEXT NOT NULL,
+    -- null until the match is over and its result has been fetched
+    won      INTEGER,
+    PRIMARY KEY (match_id, puuid)
+);
+
+CREATE INDEX IF NOT EXISTS encounters_puuid ON encounters (puuid);
+
+CREATE TABLE IF NOT EXISTS names (
+    puuid          TEXT NOT NULL,
+    name           TEXT NOT NULL,
+    first_seen     INTEGER NOT NULL,
+    last_seen      INTEGER NOT NULL,
+    -- the match the player most recently switched to this name in
+    first_match_id TEXT NOT NULL,
+    PRIMARY KEY (puuid, name)
+);
+";
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchResult {
+    Win,
+    Loss,
+}
+
+/// How often the user has run into a player before, shown next to them in the table.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncounterSummary {
+    pub times_with: u32,
+    pub times_against: u32,
+    pub last_seen: DateTime<Utc>,
+    /// the user's result in the last match with this player, if it's known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_result: Option<MatchResult>,
+}
+
+/// One match a player was seen in.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Encounter {
+    pub match_id: String,
+    pub map: String,
+    pub mode: String,
+    pub seen_at: DateTime<Utc>,
+    /// None if the player was incognito
+    pub name: Option<String>,
+    pub team: String,
+    pub agent: Option<String>,
+    pub rank: Option<String>,
+    pub party_id: String,
+    /// whether this player's team won
+    pub won: Option<bool>,
+}
+
+/// A Riot ID a player has gone by.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NameRecord {
+    pub name: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Every match and player seen through `load_match`, kept in a local SQLite database.
+/// Does nothing until [`EncounterDb::open`] succeeds.
+#[derive(Debug, Default)]
+pub struct EncounterDb(Database);
+
+fn timestamp(secs: i64) -> DateTime<Utc> {
+    Utc.timestamp_opt(secs, 0).single().unwrap_or_default()
+}
+
+impl EncounterDb {
+    pub fn open(&self, path: &Path) -> Result<()> {
+        self.0.open(path, SCHEMA)
+    }
+
+    /// Records every participant in the match. Recording the same match again replaces it.
+    ///
+    /// * `incognito` - PUUIDs of incognito players. Their names are the agent they're playing,
+    ///   so they aren't recorded. Nor are names that couldn't be loaded.
+    /// * `current_act` - UUID of the act that's on, for each player's rank. None if unknown.
+    pub fn record_match(
+        &self,
+        match_data: &ShortMatchData,
+        incognito: &HashSet<String>,
+        current_act: Option<&str>,
+    ) -> Result<()> {
+        self.0.with_conn(|conn| {
+            let tx = conn.transaction()?;
+
+            tx.execute(
+                "INSERT INTO matches (match_id, map, mode, seen_at) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (match_id) DO UPDATE SET map = ?2, mode = ?3",
+                params![
+                    match_data.match_id,
+                    match_data.map,
+                    match_data.mode,
+                    Utc::now().timestamp()
+                ],
+            )?;
+
+            for player in match_data.participants() {
+                tx.execute(
+                    "INSERT INTO encounters (match_id, puuid, name, team, agent, rank, party_id)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                     ON CONFLICT (match_id, puuid) DO UPDATE
+                     SET name = ?3, team = ?4, agent = ?5, rank = ?6, party_id = ?7",
+                    params![
+                        match_data.match_id,
+                        player.uuid,
+                        Some(&player.name).filter(|_| {
+                            !incognito.contains(&player.uuid) && name_known(&player.status.name)
+                        }),
+                        player.team,
+                        player.character.as_ref().map(|a| &a.display_name),
+                        current_act
+                            .and_then(|act| player.rank_in(act))
+                            .map(|r| &r.tier_name),
+                        player.party_id,
+                    ],
+                )?;
+            }
+
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Stores the result of a finished match.
+    ///
+    /// * `results` - Whether each team won, keyed by team as shown in `ShortPlayer::team`.
+    pub fn record_result(&self, match_id: &str, results: &HashMap<String, bool>) -> Result<()> {
+        self.0.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            for (team, won) in results {
+                tx.execute(
+                    "UPDATE encounters SET won = ?3 WHERE match_id = ?1 AND team = ?2",
+                    params![match_id, team, won],
+                )?;
+            }
+            tx.commit()?;
+            Ok(())
+        })
+    }
+
+    /// Summarises the user's previous matches with a player.
+    ///
+    /// * `user` - The user's PUUID.
+    /// * `exclude_match` - The current match, which shouldn't count.
+    pub fn summary(
+        &self,
+        puuid: &str,
+        user: &str,
+        exclude_match: &str,
+    ) -> Result<Option<EncounterSummary>> {
+        self.0.with_conn(|conn| {
+            let mut stmt = conn.prepare_cached(
+                "SELECT m.seen_at, e.team = u.team, u.won
+                 FROM encounters e
+                 JOIN matches m ON m.match_id = e.match_id
+                 JOIN encounters u ON u.match_id = e.match_id AND u.puuid = ?2
+                 WHERE e.puuid = ?1 AND e.match_id != ?3
+                 ORDER BY m.seen_at DESC",
+            )?;
+
+            let rows = stmt
+                .query_map(params![puuid, user, exclude_match], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, bool>(1)?,
+                        row.get::<_, Option<bool>>(2)?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            let Some((last_seen, _, last_won)) = rows.first().copied() else {
+                return Ok(None);
+            };
+
+            let times_with = rows.iter().filter(|(_, same_team, _)| *same_team).count() as u32;
+
+            Ok(Some(EncounterSummary {
+                times_with,
+                times_against: rows.len() as u32 - times_with,
+                last_seen: timestamp(last_seen),
+                last_result: last_won.map(|won| match won {
+                    true => MatchResult::Win,
+                    false => MatchResult::Loss,
+                }),
+            }))
+        })
+    }
+
+    /// Returns every match the player was seen in, most recent first.
+    pub fn encounters(&self, puuid: &str) -> Result<Vec<Encounter>> {
+        self.0.with_conn(|conn| {
+            let mut stmt = conn.prepare_cached(
+                "SELECT m.match_id, m.map, m.mode, m.seen_at, e.name, e.team, e.agent, e.rank,
+                        e.party_id, e.won
+                 FROM encounters e
+                 JOIN matches m ON m.match_id = e.match_id
+                 WHERE e.puuid = ?1
+                 ORDER BY m.seen_at DESC",
+            )?;
+
+            let encounters = stmt
+                .query_map(params![puuid], |row| {
+                    Ok(Encounter {
+                        match_id: row.get(0)?,
+                        map: row.get(1)?,
+                        mode: row.get(2)?,
+                        seen_at: timestamp(row.get(3)?),
+                        name: row.get(4)?,
+                        team: row.get(5)?,
+                        agent: row.get(6)?,
+                        rank: row.get(7)?,
+                        party_id: row.get(8)?,
+                        won: row.get(9)?,
+                    })
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            Ok(encounters)
+        })
+    }
+
+    /// Deletes everything recorded about a player. Returns how many encounters were removed.
+    pub fn purge(&self, puuid: &str) -> Result<usize> {
+        self.0.with_conn(|conn| {
+            let tx = conn.transaction()?;
+            let removed = tx.execute("DELETE FROM encounters WHERE puuid = ?1", params![puuid])?;
+            // matches that nobody is left in are of no use
+            tx.execute(
+                "DELETE FROM matches
+                 WHERE NOT EXISTS (SELECT 1 FROM encounters e WHERE e.match_id = matches.match_id)",
+                [],
+            )?;
+            tx.execute("DELETE FROM names WHERE puuid = ?1", params![puuid])?;
+            tx.commit()?;
+            Ok(removed)
+        })
+    }
+
+    /// Records that the player went by `name` in the match. If that's a new name for them, or one
+    /// they've switched back to, returns the name they had when we last saw them. Keeps returning
+    /// it for the rest of the match. Names that couldn't be loaded (None) aren't recorded.
+    pub fn record_name(
+        &self,
+        puuid: &str,
+        name: Option<&str>,
+        match_id: &str,
+    ) -> Result<Option<NameRecord>> {
+        self.record_name_at(puuid, name, match_id, Utc::now().timestamp())
+    }
+
+    /// [`EncounterDb::record_name`], seen at `now` (a unix timestamp, in seconds).
+    fn record_name_at(
+        &self,
+        puuid: &str,
+        name: Option<&str>,
+        match_id: &str,
+        now: i64,
+    ) -> Result<Option<NameRecord>> {
+        let Some(name) = name else {
+            return Ok(None);
+        };
+
+        self.0.with_conn(|conn| {
+            let tx = conn.transaction()?;
+
+            let other = tx
+                .query_row(
+                    "SELECT name, first_seen, last_seen FROM names
+                     WHERE puuid = ?1 AND name != ?2 AND name != ''
+                     ORDER BY last_seen DESC LIMIT 1",
+                    params![puuid, name],
+                    name_record,
+                )
+                .optional()?;
+            let last_seen = tx
+                .query_row(
+                    "SELECT last_seen FROM names WHERE puuid = ?1 AND name = ?2",
+                    params![puuid, name],
+                    |row| row.get::<_, i64>(0),
+                )
+                .optional()?;
+
+            // a name is new again if they've gone by another one since it was last seen
+            let switched = match (&other, last_seen) {
+                (Some(other), Some(last_seen)) => other.last_seen.timestamp() > last_seen,
+                (_, None) => true,
+                (None, Some(_)) => false,
+            };
+
+            tx.execute(
+                "INSERT INTO names (puuid, name, first_seen, last_seen, first_match_id)
+                 VALUES (?1, ?2, ?3, ?3, ?4)
+                 ON CONFLICT (puuid, name) DO UPDATE SET
+                     last_seen = ?3,
+                     first_match_id = CASE WHEN ?5 THEN ?4 ELSE first_match_id END",
+                params![puuid, name, now, match_id, switched],
+            )?;
+
+            let first_match_id = tx.query_row(
+                "SELECT first_match_id FROM names WHERE puuid = ?1 AND name = ?2",
+                params![puuid, name],
+                |row| row.get::<_, String>(0),
+            )?;
+
+            tx.commit()?;
+            Ok(other.filter(|_| first_match_id == match_id))
+        })
+    }
+
+    /// Returns every name the player has gone by, most recent first.
+    pub fn name_history(&self, puuid: &str) -> Result<Vec<NameRecord>> {
+        self.0.with_conn(|conn| {
+            let names = conn
+                .prepare_cached(
+                    "SELECT name, first_seen, last_seen FROM names
+                     WHERE puuid = ?1 AND name != ''
+                     ORDER BY last_seen DESC",
+                )?
+                .query_map(params![puuid], name_record)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            Ok(names)
+        })
+    }
+}
+
+/// Whether the name was actually loaded, rather than left blank.
+pub fn name_known(status: &FieldStatus) -> bool {
+    matches!(status, FieldStatus::Loaded | FieldStatus::Fallback)
+}
+
+fn name_record(row: &rusqlite::Row) -> rusqlite::Result<NameRecord> {
+    Ok(NameRecord {
+        name: row.get(0)?,
+        first_seen: timestamp(row.get(1)?),
+        last_seen: timestamp(row.get(2)?),
+    })
+}
+
+/// Fills in `PlayerExtras::encounters` for everyone but the user.
+pub fn attach_summaries(db: &EncounterDb, match_data: &mut ShortMatchData, user: &str) {
+    let match_id = match_data.match_id.clone();
+    let players = match_data.participants_mut().filter(|p| p.uuid != user);
+
+    for player in players {
+        match db.summary(&player.uuid, user, &match_id) {
+            Ok(summary) => player.extra.encounters = summary,
+            // one player's summary failing shouldn't take everyone else's with it
+            Err(why) => debug!("Unable to load encounters for {}: {why}", player.uuid),
+        }
+    }
+}
+
+/// Returns every match the player has been seen in.
+#[tauri::command]
+pub async fn get_encounters(
+    state: tauri::State<'_, crate::HauntState>,
+    puuid: String,
+) -> Result<Vec<Encounter>, ()> {
+    let encounters = state.0.encounters.encounters(&puuid).map_err(|why| {
+        warn!("Unable to load encounters for {puuid}: {why}");
+    })?;
+
+    Ok(streamer::encounters(&state, &puuid, encounters))
+}
+
+/// Returns everything recorded about a player as pretty-printed JSON.
+#[tauri::command]
+pub async fn export_encounters(
+    state: tauri::State<'_, crate::HauntState>,
+    puuid: String,
+) -> Result<String, ()> {
+    let db = &state.0.encounters;
+    let (encounters, names) = db
+        .encounters(&puuid)
+        .and_then(|encounters| Ok((encounters, db.name_history(&puuid)?)))
+        .map_err(|why| {
+            warn!("Unable to load encounters for {puuid}: {why}");
+        })?;
+
+    serde_json::to_string_pretty(&serde_json::json!({
+        "puuid": puuid,
+        "names": streamer::name_history(&state, &puuid, names),
+        "encounters": streamer::encounters(&state, &puuid, encounters),
+    }))
+    .map_err(|why| {
+        warn!("Unable to export encounters for {puuid}: {why}");
+    })
+}
+
+/// Deletes everything recorded about a player.
+#[tauri::command]
+pub async fn purge_encounters(
+    state: tauri::State<'_, crate::HauntState>,
+    puuid: String,
+) -> Result<usize, ()> {
+    info!("Purging encounters for {puuid}.");
+    state.0.encounters.purge(&puuid).map_err(|why| {
+        warn!("Unable to purge encounters for {puuid}: {why}");
+    })
+}
+
+/// Returns every Riot ID the player has gone by, most recent first.
+#[tauri::command]
+pub async fn get_name_history(
+    state: tauri::State<'_, crate::HauntState>,
+    puuid: String,
+) -> Result<Vec<NameRecord>, ()> {
+    let names = state.0.encounters.name_history(&puuid).map_err(|why| {
+        warn!("Unable to load name history for {puuid}: {why}");
+    })?;
+
+    Ok(streamer::name_history(&state, &puuid, names))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open() -> EncounterDb {
+        let db = EncounterDb::default();
+        db.open(Path::new(":memory:")).unwrap();
+        db
+    }
+
+    fn previous_name(previous: Result<Option<NameRecord>>) -> Option<String> {
+        previous.unwrap().map(|previous| previous.name)
+    }
+
+    #[test]
+    fn switching_back_to_an_old_name_is_flagged() {
+        let db = open();
+
+        assert_eq!(
+            previous_name(db.record_name_at("p", Some("A #1"), "m1", 100)),
+            None
+        );
+        assert_eq!(
+            previous_name(db.record_name_at("p", Some("B #1"), "m2", 200)).as_deref(),
+            Some("A #1")
+        );
+        assert_eq!(
+            previous_name(db.record_name_at("p", Some("A #1"), "m3", 300)).as_deref(),
+            Some("B #1")
+        );
+        // for the rest of the match
+        assert_eq!(
+            previous_name(db.record_name_at("p", Some("A #1"), "m3", 310)).as_deref(),
+            Some("B #1")
+        );
+        assert_eq!(
+            previous_name(db.record_name_at("p", Some("A #1"), "m4", 400)),
+            None
+        );
+    }
+
+    #[test]
+    fn names_that_failed_to_load_are_not_previous_names() {
+        let db = open();
+
+        assert_eq!(previous_name(db.record_name_at("p", None, "m1", 100)), None);
+        assert_eq!(
+            previous_name(db.record_name_at("p", Some("A #1"), "m2", 200)),
+            None
+        );
+        assert_eq!(previous_name(db.record_name_at("p", None, "m3", 300)), None);
+        assert_eq!(
+            previous_name(db.record_name_at("p", Some("A #1"), "m4", 400)),
+            None
+        );
+        assert_eq!(db.name_history("p").unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,72 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::api::{
+    asset_urls, commands::ShortMatchData, friends::FriendChange, lobby::PlayerUpdate,
+    notes::AvoidAlert, settings::Settings, streamer, watcher::ClientState,
+};
+
+/// Name of the event every [`MatchEvent`] is emitted under.
+pub const MATCH_EVENT: &str = "match-event";
+/// Emitted with the ValAPI version once agents, ranks etc. are available. Until then, agents and
+/// ranks are sent as UUIDs and raw tier indices.
+pub const CONTENT_READY_EVENT: &str = "content-ready";
+/// Emitted with the new settings whenever they're changed or reset.
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+/// Emitted with a [`FriendChange`] while the `friendNotifications` setting is on.
+pub const FRIEND_CHANGED_EVENT: &str = "friend-changed";
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "camelCase")]
+pub enum MatchEvent {
+    StateChanged {
+        from: ClientState,
+        to: ClientState,
+    },
+    /// map, mode, teams and agents of a match whose players are still loading
+    MatchSkeleton(ShortMatchData),
+    /// one player's name or rank history finished loading
+    PlayerEnriched(PlayerUpdate),
+    /// a new match was found and fully loaded
+    MatchLoaded(ShortMatchData),
+    /// agent select or teams changed in the current match
+    MatchUpdated(ShortMatchData),
+    /// an avoid-listed player is in the match that just started loading
+    AvoidAlert(AvoidAlert),
+}
+
+/// Emits the event to every window, masked if streamer mode is on. Failing to emit is logged
+/// rather than returned, since there's nothing the caller could do about it anyway.
+pub fn emit(app: &AppHandle, event: MatchEvent) {
+    let event = streamer::event(&app.state::<crate::HauntState>(), event);
+    let event = asset_urls::event(event);
+    trace!("Emitting match event: {:?}", event);
+    if let Err(why) = app.emit_all(MATCH_EVENT, event) {
+        warn!("Unable to emit match event: {why}");
+    }
+}
+
+/// Tells every window that ValAPI content has loaded.
+pub fn emit_content_ready(app: &AppHandle, version: &str) {
+    debug!("Emitting content ready event for {version}");
+    if let Err(why) = app.emit_all(CONTENT_READY_EVENT, version) {
+        warn!("Unable to emit content ready event: {why}");
+    }
+}
+
+/// Tells every window the settings changed, so other windows don't show stale ones.
+pub fn emit_settings_changed(app: &AppHandle, settings: &Settings) {
+    debug!("Emitting settings changed event");
+    if let Err(why) = app.emit_all(SETTINGS_CHANGED_EVENT, settings) {
+        warn!("Unable to emit settings changed event: {why}");
+    }
+}
+
+/// Tells every window a friend's status changed, masked if streamer mode is on.
+pub fn emit_friend_changed(app: &AppHandle, change: FriendChange) {
+    let change = streamer::friend_change(&app.state::<crate::HauntState>(), change);
+    debug!("Emitting friend changed event: {:?}", change);
+    if let Err(why) = app.emit_all(FRIEND_CHANGED_EVENT, change) {
+        warn!("Unable to emit friend changed event: {why}");
+    }
+}
@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::api::{asset_urls, commands, events, streamer};
+
+pub use haunt_core::friends::{FriendChange, FriendStatus};
+
+/// How often friends are checked while `friendNotifications` is on. Nothing is polled otherwise.
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Returns everyone on the user's friends list and what they're doing, online friends first.
+/// Err(false) if the user isn't logged in, Err(true) if the friends list can't be loaded.
+#[tauri::command]
+pub async fn get_friends(
+    state: tauri::State<'_, crate::HauntState>,
+) -> Result<Vec<FriendStatus>, bool> {
+    let (lockfile_config, _, _) = commands::load_configs(&state).await?;
+
+    let friends = haunt_core::friends::load(&state.0.client, &lockfile_config)
+        .await
+        .map_err(|why| {
+            error!("Unable to load friends: {why}");
+            true
+        })?;
+
+    Ok(asset_urls::friends(streamer::friends(&state, friends)))
+}
+
+/// Spawns the background task that emits a [`FriendChange`] whenever a friend's status changes.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // changes are only known from the second load on
+        let mut previous: Option<Vec<FriendStatus>> = None;
+
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let state = app.state::<crate::HauntState>();
+            if !state.0.client.settings.get().friend_notifications {
+                previous = None;
+                continue;
+            }
+
+            let Ok((lockfile_config, _, _)) = commands::load_configs(&state).await else {
+                previous = None;
+                continue;
+            };
+
+            match haunt_core::friends::load(&state.0.client, &lockfile_config).await {
+                Ok(friends) => {
+                    let changes = previous
+                        .as_deref()
+                        .map(|previous| haunt_core::friends::changes(previous, &friends))
+                        .unwrap_or_default();
+                    for change in changes {
+                        info!(
+                            "{} went from {:?} to {:?}.",
+                            change.puuid, change.from, change.to
+                        );
+                        events::emit_friend_changed(&app, change);
+                    }
+
+                    previous = Some(friends);
+                }
+                Err(why) => debug!("Unable to load friends: {why}"),
+            }
+        }
+    });
+}
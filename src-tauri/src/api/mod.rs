@@ -1,6 +1,14 @@
+pub use haunt_core::{lobby, local, lockfile, login, pvp, source, traffic, valapi};
+
+mod asset_urls;
 pub mod commands;
-pub mod local;
-pub mod lockfile;
+pub mod content;
+pub mod db;
+pub mod encounters;
+pub mod events;
+pub mod friends;
+pub mod notes;
 mod player;
-mod pvp;
-pub mod valapi;
+pub mod settings;
+pub mod streamer;
+pub mod watcher;
@@ -0,0 +1,256 @@
+use std::path::Path;
+
+use chrono::{DateTime, TimeZone, Utc};
+use color_eyre::eyre::{bail, Result};
+use rusqlite::{params, OptionalExtension, Row};
+use serde::Serialize;
+
+use crate::api::{commands::ShortMatchData, db::Database};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS notes (
+    puuid      TEXT PRIMARY KEY,
+    note       TEXT NOT NULL,
+    -- json array of strings
+    tags       TEXT NOT NULL,
+    avoid      INTEGER NOT NULL,
+    -- unix timestamp, in seconds
+    updated_at INTEGER NOT NULL
+);
+";
+
+/// The user's notes on a player. Keyed by PUUID so they survive name changes.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayerNote {
+    pub puuid: String,
+    pub note: String,
+    pub tags: Vec<String>,
+    /// alert the user when this player shows up in a match
+    pub avoid: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PlayerNote {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        let tags = row.get::<_, String>(2)?;
+
+        Ok(PlayerNote {
+            puuid: row.get(0)?,
+            note: row.get(1)?,
+            tags: serde_json::from_str(&tags).unwrap_or_default(),
+            avoid: row.get(3)?,
+            updated_at: Utc
+                .timestamp_opt(row.get(4)?, 0)
+                .single()
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Sent when an avoid-listed player turns up in a new match.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvoidAlert {
+    pub match_id: String,
+    /// the name shown in the match, which is the agent name for incognito players
+    pub name: String,
+    pub note: PlayerNote,
+}
+
+#[derive(Debug, Default)]
+pub struct NoteStore(Database);
+
+impl NoteStore {
+    pub fn open(&self, path: &Path) -> Result<()> {
+        self.0.open(path, SCHEMA)
+    }
+
+    pub fn get(&self, puuid: &str) -> Result<Option<PlayerNote>> {
+        self.0.with_conn(|conn| {
+            let note = conn
+                .prepare_cached(
+                    "SELECT puuid, note, tags, avoid, updated_at FROM notes WHERE puuid = ?1",
+                )?
+                .query_row(params![puuid], PlayerNote::from_row)
+                .optional()?;
+
+            Ok(note)
+        })
+    }
+
+    /// Returns every note, most recently updated first.
+    pub fn list(&self) -> Result<Vec<PlayerNote>> {
+        self.0.with_conn(|conn| {
+            let notes = conn
+                .prepare_cached(
+                    "SELECT puuid, note, tags, avoid, updated_at FROM notes
+                     ORDER BY updated_at DESC",
+                )?
+                .query_map([], PlayerNote::from_row)?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            Ok(notes)
+        })
+    }
+
+    fn save(&self, note: &PlayerNote) -> Result<()> {
+        self.0.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO notes (puuid, note, tags, avoid, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT (puuid) DO UPDATE
+                 SET note = ?2, tags = ?3, avoid = ?4, updated_at = ?5",
+                params![
+                    note.puuid,
+                    note.note,
+                    serde_json::to_string(&note.tags)?,
+                    note.avoid,
+                    note.updated_at.timestamp(),
+                ],
+            )?;
+
+            Ok(())
+        })
+    }
+
+    pub fn create(
+        &self,
+        puuid: &str,
+        note: String,
+        tags: Vec<String>,
+        avoid: bool,
+    ) -> Result<PlayerNote> {
+        if self.get(puuid)?.is_some() {
+            bail!("{puuid} already has a note.");
+        }
+
+        let note = PlayerNote {
+            puuid: puuid.to_string(),
+            note,
+            tags,
+            avoid,
+            updated_at: Utc::now(),
+        };
+        self.save(&note)?;
+
+        Ok(note)
+    }
+
+    /// Changes the given fields of an existing note.
+    pub fn update(
+        &self,
+        puuid: &str,
+        note: Option<String>,
+        tags: Option<Vec<String>>,
+        avoid: Option<bool>,
+    ) -> Result<PlayerNote> {
+        let Some(mut existing) = self.get(puuid)? else {
+            bail!("{puuid} doesn't have a note.");
+        };
+
+        if let Some(note) = note {
+            existing.note = note;
+        }
+        if let Some(tags) = tags {
+            existing.tags = tags;
+        }
+        if let Some(avoid) = avoid {
+            existing.avoid = avoid;
+        }
+        existing.updated_at = Utc::now();
+        self.save(&existing)?;
+
+        Ok(existing)
+    }
+
+    /// Returns whether there was a note to delete.
+    pub fn delete(&self, puuid: &str) -> Result<bool> {
+        self.0.with_conn(|conn| {
+            let removed = conn.execute("DELETE FROM notes WHERE puuid = ?1", params![puuid])?;
+            Ok(removed > 0)
+        })
+    }
+}
+
+/// Fills in `PlayerExtras::notes` for everyone in the match.
+pub fn attach_notes(store: &NoteStore, match_data: &mut ShortMatchData) {
+    for player in match_data.participants_mut() {
+        match store.get(&player.uuid) {
+            Ok(note) => player.extra.notes = note,
+            Err(why) => debug!("Unable to load notes for {}: {why}", player.uuid),
+        }
+    }
+}
+
+/// An alert for each avoid-listed player, from the notes [`attach_notes`] filled in. Names are
+/// only known once the match has loaded, so this waits until then.
+pub fn avoid_alerts(match_data: &ShortMatchData) -> Vec<AvoidAlert> {
+    match_data
+        .participants()
+        .filter_map(|player| {
+            let note = player.extra.notes.as_ref().filter(|n| n.avoid)?;
+            Some(AvoidAlert {
+                match_id: match_data.match_id.clone(),
+                name: player.name.clone(),
+                note: note.clone(),
+            })
+        })
+        .collect()
+}
+
+fn log_err<T>(res: Result<T>, action: &str) -> Result<T, ()> {
+    res.map_err(|why| warn!("Unable to {action}: {why}"))
+}
+
+#[tauri::command]
+pub async fn create_note(
+    state: tauri::State<'_, crate::HauntState>,
+    puuid: String,
+    note: String,
+    tags: Vec<String>,
+    avoid: bool,
+) -> Result<PlayerNote, ()> {
+    log_err(
+        state.0.notes.create(&puuid, note, tags, avoid),
+        "create note",
+    )
+}
+
+/// Returns the note for a player, or None if there isn't one.
+#[tauri::command]
+pub async fn get_note(
+    state: tauri::State<'_, crate::HauntState>,
+    puuid: String,
+) -> Result<Option<PlayerNote>, ()> {
+    log_err(state.0.notes.get(&puuid), "load note")
+}
+
+#[tauri::command]
+pub async fn list_notes(state: tauri::State<'_, crate::HauntState>) -> Result<Vec<PlayerNote>, ()> {
+    log_err(state.0.notes.list(), "load notes")
+}
+
+/// Updates a player's note. Fields that are left out aren't changed.
+#[tauri::command]
+pub async fn update_note(
+    state: tauri::State<'_, crate::HauntState>,
+    puuid: String,
+    note: Option<String>,
+    tags: Option<Vec<String>>,
+    avoid: Option<bool>,
+) -> Result<PlayerNote, ()> {
+    log_err(
+        state.0.notes.update(&puuid, note, tags, avoid),
+        "update note",
+    )
+}
+
+/// Returns whether there was a note to delete.
+#[tauri::command]
+pub async fn delete_note(
+    state: tauri::State<'_, crate::HauntState>,
+    puuid: String,
+) -> Result<bool, ()> {
+    log_err(state.0.notes.delete(&puuid), "delete note")
+}
@@ -0,0 +1,132 @@
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager};
+
+use crate::api::{
+    content,
+    events::{self, MatchEvent},
+    source::Backend,
+};
+
+pub use haunt_core::settings::{Settings, SettingsError};
+
+/// Settings live in the config dir rather than the data dir, so they roam with the user's profile.
+fn settings_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path()
+        .app_config_dir()
+        .ok()
+        .map(|dir| dir.join("settings.json"))
+}
+
+/// Loads the saved settings, then switches to the saved data source unless `HAUNT_REPLAY` or
+/// `HAUNT_BACKEND` picked one already.
+pub fn load(app: &AppHandle) {
+    let state = app.state::<crate::HauntState>();
+    let client = &state.0.client;
+
+    match settings_path(app) {
+        Some(path) => client.settings.load(path),
+        None => warn!("No app config directory. Settings won't be saved."),
+    }
+
+    let backend = match Backend::from_env() {
+        Backend::Live => client.settings.get().data_source.clone(),
+        backend => backend,
+    };
+    if let Err(why) = client.source.set(backend) {
+        warn!("Unable to switch data source, staying live: {why}");
+    }
+}
+
+fn invalid_source(why: impl std::fmt::Display) -> SettingsError {
+    SettingsError::Invalid {
+        field: "dataSource".to_string(),
+        reason: why.to_string(),
+    }
+}
+
+/// Switches where lobbies are loaded from. The current login and match belong to the old source,
+/// so they're dropped and the frontend has to log in again. Nothing changes if the source can't
+/// be switched to.
+pub(crate) async fn switch_source(
+    state: &crate::HauntState,
+    backend: Backend,
+) -> Result<(), SettingsError> {
+    state.0.client.source.set(backend).map_err(invalid_source)?;
+
+    *state.0.lockfile_config.lock().await = None;
+    *state.0.entitlements_config.lock().await = None;
+    *state.0.session_config.lock().await = None;
+    *state.0.match_cache.lock().await = None;
+    state.0.streamer.set_user(None);
+
+    Ok(())
+}
+
+/// Saves `settings` and applies whatever changed, then tells every window. Nothing is saved if
+/// the new data source can't be switched to.
+async fn apply(app: &AppHandle, settings: Settings) -> Result<Settings, SettingsError> {
+    let state = app.state::<crate::HauntState>();
+    let client = &state.0.client;
+
+    let source_changed = client.settings.get().data_source != settings.data_source;
+    if source_changed {
+        settings.data_source.validate().map_err(invalid_source)?;
+    }
+
+    let previous = client.settings.set(settings.clone())?;
+
+    // a recording can still start between validating and switching
+    if source_changed {
+        if let Err(why) = switch_source(&state, settings.data_source.clone()).await {
+            if let Err(why) = client.settings.set((*previous).clone()) {
+                warn!("Unable to restore the previous settings: {why}");
+            }
+            return Err(why);
+        }
+    }
+
+    // the cache is keyed by language, so this refetches everything in the new one
+    if previous.language != settings.language {
+        content::spawn_refresh(app.clone());
+    }
+
+    // redraw the current match with or without streamer mode
+    if previous.privacy != settings.privacy {
+        if let Some(match_data) = state.0.match_cache.lock().await.clone() {
+            events::emit(app, MatchEvent::MatchUpdated(match_data));
+        }
+    }
+
+    events::emit_settings_changed(app, &settings);
+    Ok(settings)
+}
+
+/// Changes a single setting, keeping the rest as they are.
+pub(crate) async fn update(
+    app: &AppHandle,
+    change: impl FnOnce(&mut Settings),
+) -> Result<Settings, SettingsError> {
+    let state = app.state::<crate::HauntState>();
+    let mut settings = (*state.0.client.settings.get()).clone();
+    change(&mut settings);
+
+    apply(app, settings).await
+}
+
+#[tauri::command]
+pub fn get_settings(state: tauri::State<'_, crate::HauntState>) -> Settings {
+    (*state.0.client.settings.get()).clone()
+}
+
+/// Replaces every setting. Err names the first invalid setting, or why they couldn't be saved.
+#[tauri::command]
+pub async fn set_settings(app: AppHandle, settings: Settings) -> Result<Settings, SettingsError> {
+    apply(&app, settings).await
+}
+
+/// Goes back to the default settings.
+#[tauri::command]
+pub async fn reset_settings(app: AppHandle) -> Result<Settings, SettingsError> {
+    apply(&app, Settings::default()).await
+}
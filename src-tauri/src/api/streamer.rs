@@ -0,0 +1,130 @@
+//! Streamer mode for everything sent to the frontend. Lobbies are masked on their way out, so the
+//! match cache and encounter history keep the real names.
+
+use crate::api::{
+    commands::ShortMatchData,
+    encounters::{Encounter, NameRecord},
+    events::MatchEvent,
+    friends::{FriendChange, FriendStatus},
+};
+
+pub use haunt_core::streamer::{StreamerMask, OWN_NAME};
+
+/// Masks the lobby if streamer mode is on. Old names would give masked players away, so they're
+/// dropped too.
+pub fn lobby(state: &crate::HauntState, mut lobby: ShortMatchData) -> ShortMatchData {
+    let settings = state.0.client.settings.get();
+    if settings.privacy.streamer_mode {
+        state
+            .0
+            .streamer
+            .lobby(&mut lobby, settings.privacy.hide_own_name, |extra| {
+                extra.previous_name = None
+            });
+    }
+
+    lobby
+}
+
+/// Masks every name and lobby in the event if streamer mode is on.
+pub fn event(state: &crate::HauntState, event: MatchEvent) -> MatchEvent {
+    let settings = state.0.client.settings.get();
+    if !settings.privacy.streamer_mode {
+        return event;
+    }
+
+    let hide_own_name = settings.privacy.hide_own_name;
+    match event {
+        MatchEvent::MatchSkeleton(match_data) => {
+            MatchEvent::MatchSkeleton(lobby(state, match_data))
+        }
+        MatchEvent::MatchLoaded(match_data) => MatchEvent::MatchLoaded(lobby(state, match_data)),
+        MatchEvent::MatchUpdated(match_data) => MatchEvent::MatchUpdated(lobby(state, match_data)),
+        MatchEvent::PlayerEnriched(mut update) => {
+            state.0.streamer.update(&mut update, hide_own_name);
+            MatchEvent::PlayerEnriched(update)
+        }
+        MatchEvent::AvoidAlert(mut alert) => {
+            if let Some(name) = state.0.streamer.name(&alert.note.puuid, hide_own_name) {
+                alert.name = name;
+            }
+            MatchEvent::AvoidAlert(alert)
+        }
+        event @ MatchEvent::StateChanged { .. } => event,
+    }
+}
+
+/// Friends outside the user's party are masked like everyone else.
+pub fn friends(state: &crate::HauntState, mut friends: Vec<FriendStatus>) -> Vec<FriendStatus> {
+    let privacy = &state.0.client.settings.get().privacy;
+    if !privacy.streamer_mode {
+        return friends;
+    }
+
+    for friend in &mut friends {
+        if let Some(name) = state.0.streamer.name(&friend.puuid, privacy.hide_own_name) {
+            friend.name = name;
+        }
+    }
+
+    friends
+}
+
+pub fn friend_change(state: &crate::HauntState, mut change: FriendChange) -> FriendChange {
+    let privacy = &state.0.client.settings.get().privacy;
+    if privacy.streamer_mode {
+        if let Some(name) = state.0.streamer.name(&change.puuid, privacy.hide_own_name) {
+            change.name = name;
+        }
+    }
+
+    change
+}
+
+/// The user's own name and tag, unless they're hiding them.
+pub fn own_name(state: &crate::HauntState, name: String, tag: String) -> (String, String) {
+    let privacy = &state.0.client.settings.get().privacy;
+    match privacy.streamer_mode && privacy.hide_own_name {
+        true => (OWN_NAME.to_string(), String::new()),
+        false => (name, tag),
+    }
+}
+
+/// Shows past matches under the name the player has in the current match.
+pub fn encounters(
+    state: &crate::HauntState,
+    puuid: &str,
+    mut encounters: Vec<Encounter>,
+) -> Vec<Encounter> {
+    let privacy = &state.0.client.settings.get().privacy;
+    if !privacy.streamer_mode {
+        return encounters;
+    }
+
+    if let Some(name) = state.0.streamer.name(puuid, privacy.hide_own_name) {
+        for encounter in &mut encounters {
+            encounter.name = Some(name.clone());
+        }
+    }
+
+    encounters
+}
+
+/// Old names are the player's real ones, so there's nothing to show for masked players.
+pub fn name_history(
+    state: &crate::HauntState,
+    puuid: &str,
+    names: Vec<NameRecord>,
+) -> Vec<NameRecord> {
+    let privacy = &state.0.client.settings.get().privacy;
+    match privacy.streamer_mode
+        && state
+            .0
+            .streamer
+            .name(puuid, privacy.hide_own_name)
+            .is_some()
+    {
+        true => Vec::new(),
+        false => names,
+    }
+}
@@ -0,0 +1,187 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use color_eyre::eyre::{eyre, Result};
+use tauri::{AppHandle, Manager};
+
+use crate::api::{
+    self,
+    commands::{self, ShortMatchData},
+    events::{self, MatchEvent},
+    local::presence,
+    login::Login,
+    settings::{self, SettingsError},
+    source::Backend,
+};
+use haunt_core::{
+    watcher::{Change, LobbyLoader, LobbyWatcher},
+    Client,
+};
+
+pub use haunt_core::watcher::{ClientState, PollIntervals};
+
+/// How many times to try fetching a finished match's result, and how long to wait before each.
+const RESULT_ATTEMPTS: u32 = 5;
+const RESULT_DELAY: Duration = Duration::from_secs(15);
+
+/// Loads matches the way the `load_match` command does, so the frontend gets the skeleton and
+/// each player as they come in, with encounters and notes attached.
+#[derive(Debug)]
+struct AppLoader(AppHandle);
+
+#[async_trait]
+impl LobbyLoader for AppLoader {
+    type Extra = commands::PlayerExtras;
+
+    async fn load(
+        &self,
+        _client: &Client,
+        login: &Login,
+        match_id: &str,
+        presences: &[presence::Player],
+    ) -> Result<ShortMatchData> {
+        let state = self.0.state::<crate::HauntState>();
+        commands::load_match_data(
+            &self.0,
+            &state,
+            &login.entitlements,
+            &login.session,
+            match_id,
+            presences,
+        )
+        .await
+        .map_err(|_| eyre!("unable to load match players"))
+    }
+
+    async fn update(
+        &self,
+        _client: &Client,
+        login: &Login,
+        lobby: &mut ShortMatchData,
+        presences: &[presence::Player],
+    ) -> Result<()> {
+        let state = self.0.state::<crate::HauntState>();
+        *lobby = commands::quick_update_match_data(
+            &state,
+            &login.entitlements,
+            &login.session,
+            &lobby.match_id,
+            presences,
+        )
+        .await
+        .map_err(|_| eyre!("the match cache is missing or stale"))?;
+
+        Ok(())
+    }
+}
+
+/// Spawns the background task that follows the client through the game loop.
+pub fn spawn(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut watcher = LobbyWatcher::new(AppLoader(app.clone()));
+
+        loop {
+            tick(&app, &mut watcher).await;
+
+            let interval = {
+                let state = app.state::<crate::HauntState>();
+                let settings = state.0.client.settings.get();
+                settings.poll_intervals.for_state(watcher.state())
+            };
+
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+/// Polls the client once and sends the frontend whatever changed.
+async fn tick(app: &AppHandle, watcher: &mut LobbyWatcher<AppLoader>) {
+    let state = app.state::<crate::HauntState>();
+    // the lobby is dropped once the match ends, so its ID is kept for the result
+    let current = watcher.lobby().map(|lobby| lobby.match_id.clone());
+
+    for change in watcher.tick(&state.0.client).await {
+        match change {
+            Change::State { from, to } => {
+                if let (ClientState::Ingame, Some(finished)) = (from, current.clone()) {
+                    // results are only kept in the encounter database, which only holds live
+                    // matches
+                    let settings = state.0.client.settings.get();
+                    if settings.enrichments.match_results
+                        && settings.privacy.record_encounters
+                        && state.0.client.source.backend() == Backend::Live
+                    {
+                        spawn_record_result(app.clone(), finished);
+                    }
+                }
+
+                events::emit(app, MatchEvent::StateChanged { from, to });
+            }
+            // the frontend logs in by itself, and shows why that failed
+            Change::LoginFailed(_) => (),
+            Change::Loaded(lobby) => events::emit(app, MatchEvent::MatchLoaded(lobby)),
+            Change::Updated(lobby) => events::emit(app, MatchEvent::MatchUpdated(lobby)),
+        }
+    }
+}
+
+/// Fetches the result of a finished match into the encounter database. Match details take a
+/// little while to show up after the match ends, so this retries a few times.
+fn spawn_record_result(app: AppHandle, match_id: String) {
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<crate::HauntState>();
+
+        for attempt in 1..=RESULT_ATTEMPTS {
+            tokio::time::sleep(RESULT_DELAY).await;
+
+            let Ok((_, entitlements_config, session_config)) = commands::load_configs(&state).await
+            else {
+                return;
+            };
+
+            let results = api::pvp::matchdetails::get_match_results(
+                &match_id,
+                &entitlements_config,
+                &state.0.client.http,
+                &state.0.client.rate_limiter,
+                &session_config,
+            )
+            .await;
+
+            match results {
+                Ok(results) if !results.is_empty() => {
+                    if let Err(why) = state.0.encounters.record_result(&match_id, &results) {
+                        warn!("Unable to record result of {match_id}: {why}");
+                    }
+                    return;
+                }
+                Ok(_) => debug!("No result for {match_id} yet ({attempt}/{RESULT_ATTEMPTS})."),
+                Err(why) => {
+                    debug!("No result for {match_id} yet ({attempt}/{RESULT_ATTEMPTS}): {why}")
+                }
+            }
+        }
+
+        info!("Gave up on fetching the result of {match_id}.");
+    });
+}
+
+/// Changes how often the background watcher polls in each state. Saved as the `pollIntervals`
+/// setting.
+#[tauri::command]
+pub async fn set_poll_intervals(
+    app: AppHandle,
+    intervals: PollIntervals,
+) -> Result<(), SettingsError> {
+    settings::update(&app, |settings| settings.poll_intervals = intervals).await?;
+
+    Ok(())
+}
+
+/// Returns the current poll intervals.
+#[tauri::command]
+pub async fn get_poll_intervals(
+    state: tauri::State<'_, crate::HauntState>,
+) -> Result<PollIntervals, ()> {
+    Ok(state.0.client.settings.get().poll_intervals.clone())
+}
@@ -16,11 +16,10 @@ use api::commands::{self, ShortMatchData};
 
 #[derive(Debug, Default)]
 struct InnerState {
-    http: reqwest::Client,
-    offline_http: reqwest::Client, // used for local cnx with tls disabled
-
-    agents: Vec<api::valapi::agents::Agent>,
-    competitive_tiers: Vec<api::valapi::seasons::CompetitiveTier>,
+    client: haunt_core::Client,
+    encounters: api::encounters::EncounterDb,
+    notes: api::notes::NoteStore,
+    streamer: api::streamer::StreamerMask,
 
     lockfile_config: Mutex<Option<api::lockfile::Config>>,
     entitlements_config: Mutex<Option<api::local::entitlements::Config>>,
@@ -44,14 +43,14 @@ fn main() -> Result<()> {
         .with(EnvFilter::from_default_env())
         .init();
 
-    let http = reqwest::Client::builder()
-        .build()
-        .expect("failed to build reqwest client");
+    // HAUNT_RECORD, for bug reports
+    if let Err(why) = haunt_core::traffic::from_env() {
+        warn!("Unable to start recording traffic: {why}");
+    }
 
-    let offline_http = reqwest::Client::builder()
-        .danger_accept_invalid_certs(true)
-        .build()
-        .expect("failed to build reqwest client");
+    let client = haunt_core::Client::new()?;
+    // the asset protocol handler lives outside of the state, so it gets its own handle
+    let http = client.http.clone();
 
     tauri::Builder::default()
         .setup(|app| {
@@ -70,21 +69,79 @@ fn main() -> Result<()> {
             window.maximize().unwrap();
             window.unmaximize().unwrap();
 
+            // before anything is fetched, since the language and data source depend on them
+            api::settings::load(&app.handle());
+
+            // use whatever was cached last run, then fetch anything newer in the background.
+            // never block startup on the network, commands fall back to UUIDs until it loads
+            let cached = api::content::content_dir(&app.handle())
+                .map(|dir| api::valapi::content::load_from_disk(&dir));
+            match cached {
+                Some(Ok(content)) => app.state::<HauntState>().0.client.content.set(content),
+                Some(Err(why)) => info!("No usable ValAPI content cache: {why}"),
+                None => warn!("No app data directory. Starting without cached ValAPI content."),
+            }
+
+            match app.path().app_local_data_dir() {
+                Ok(dir) => {
+                    let state = app.state::<HauntState>();
+                    state.0.client.response_cache.load(dir.join("response_cache.json"));
+                    if let Err(why) = state.0.encounters.open(&dir.join("encounters.db")) {
+                        warn!("Unable to open the encounter database: {why}");
+                    }
+                    if let Err(why) = state.0.notes.open(&dir.join("notes.db")) {
+                        warn!("Unable to open the notes database: {why}");
+                    }
+                }
+                Err(why) => {
+                    warn!("No app data directory. Responses, encounters and notes won't be saved: {why}")
+                }
+            }
+
+            api::content::spawn_refresh(app.handle().clone());
+            api::watcher::spawn(app.handle().clone());
+            api::friends::spawn(app.handle().clone());
+
             Ok(())
         })
         .manage(HauntState(Arc::new(InnerState {
-            http,
-            offline_http,
-
-            agents: api::valapi::agents::load_agent_map(),
-            competitive_tiers: api::valapi::seasons::load_competitive_tiers(),
-
+            client,
             ..Default::default()
         })))
+        .register_asynchronous_uri_scheme_protocol(
+            api::valapi::images::PROTOCOL,
+            api::valapi::images::protocol(http),
+        )
         .invoke_handler(tauri::generate_handler![
             commands::login,
             commands::load_match,
             commands::quick_update_match,
+            commands::load_custom_game,
+            commands::set_custom_game_settings,
+            commands::move_custom_game_player,
+            commands::invalidate_cache,
+            commands::export_lobby,
+            commands::start_recording,
+            commands::stop_recording,
+            commands::get_data_source,
+            commands::set_data_source,
+            api::friends::get_friends,
+            api::encounters::get_encounters,
+            api::encounters::export_encounters,
+            api::encounters::purge_encounters,
+            api::encounters::get_name_history,
+            api::notes::create_note,
+            api::notes::get_note,
+            api::notes::list_notes,
+            api::notes::update_note,
+            api::notes::delete_note,
+            api::watcher::get_poll_intervals,
+            api::watcher::set_poll_intervals,
+            api::settings::get_settings,
+            api::settings::set_settings,
+            api::settings::reset_settings,
+            api::content::update_files,
+            api::content::is_content_ready,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
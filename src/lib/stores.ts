@@ -1,6 +1,7 @@
 import { defineStore } from "pinia";
 import { ref } from "vue";
-import { MatchData } from "./types";
+import { listen } from "@tauri-apps/api/event";
+import { ClientState, MatchData, MatchEvent, PlayerUpdate } from "./types";
 
 export const useUserProfileStore = defineStore("userProfile", () => {
   const username = ref("pluh playa");
@@ -12,6 +13,51 @@ export const useUserProfileStore = defineStore("userProfile", () => {
 
 export const useGameDataStore = defineStore("gameData", () => {
   const gameData = ref<MatchData>();
+  const clientState = ref<ClientState>("offline");
 
-  return { gameData };
+  function enrich(update: PlayerUpdate) {
+    // a late update from the previous match could otherwise land on the same player in this one
+    if (update.matchId !== gameData.value?.matchId) return;
+
+    const player = [
+      ...(gameData.value?.players ?? []),
+      ...(gameData.value?.spectators ?? []),
+    ].find((p) => p.uuid === update.uuid);
+    if (!player) return;
+
+    if (update.name !== undefined) player.name = update.name;
+    if (update.nameStatus) player.status.name = update.nameStatus;
+    if (update.rankHistory) player.rankHistory = update.rankHistory;
+    if (update.rankStatus) player.status.rankHistory = update.rankStatus;
+    player.loadState = update.loadState;
+  }
+
+  function apply(event: MatchEvent) {
+    switch (event.type) {
+      case "stateChanged":
+        clientState.value = event.data.to;
+        // the lobby is gone once the client leaves agent select or the match
+        if (event.data.to !== "pregame" && event.data.to !== "ingame")
+          gameData.value = undefined;
+        break;
+      case "matchSkeleton":
+      case "matchLoaded":
+      case "matchUpdated":
+        gameData.value = event.data;
+        break;
+      case "playerEnriched":
+        enrich(event.data);
+        break;
+      case "avoidAlert":
+        console.warn(`${event.data.name} is on your avoid list`, event.data);
+        break;
+    }
+  }
+
+  // the background watcher polls the client, so the views only need to follow its events
+  listen<MatchEvent>("match-event", (event) => apply(event.payload)).catch(
+    (e) => console.error("Unable to listen for match events", e)
+  );
+
+  return { gameData, clientState };
 });
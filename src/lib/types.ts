@@ -1,30 +1,190 @@
 export type MatchData = {
+  matchId: string;
   ingame: boolean;
   map: string;
   mode: string;
+  layout: "teams" | "teamDeathmatch" | "freeForAll";
+  teamBased: boolean;
   players: Player[];
+  spectators: Player[];
 };
 
 export type Player = {
   uuid: string;
   name: string;
-  team: "blue" | "red" | "unknown";
+  // free-for-all modes give every player their own team ID
+  team: "blue" | "red" | "neutral" | string;
   character?: Agent;
+  // false while the agent is only hovered
+  locked: boolean;
   title: string;
   accountLevel?: number;
   rankHistory: CompetitiveTier[];
   partyId: string;
+  role: "player" | "coach" | "observer";
+  loadState: LoadState;
+  status: PlayerStatus;
+  // previous matches with this player, absent for the user and strangers
+  encounters?: EncounterSummary;
+  notes?: PlayerNote;
+  // Riot ID when we last saw them, if it's changed since
+  previousName?: NameRecord;
+};
+
+export type FieldStatus =
+  | { status: "pending" }
+  | { status: "loaded" }
+  // the pd service failed, so this came from the player's presence
+  | { status: "fallback" }
+  | { status: "unavailable" }
+  | { status: "error"; reason: string };
+
+export type PlayerStatus = {
+  name: FieldStatus;
+  rankHistory: FieldStatus;
+};
+
+export type LoadState = "loading" | "loaded";
+
+// only the fields that just finished loading are present
+export type PlayerUpdate = {
+  matchId: string;
+  uuid: string;
+  name?: string;
+  nameStatus?: FieldStatus;
+  rankHistory?: CompetitiveTier[];
+  rankStatus?: FieldStatus;
+  loadState: LoadState;
 };
 
 export type Agent = {
   uuid: string;
   displayName: string;
-  displayIcon: string;
+  displayIcon?: string;
 };
 
 export type CompetitiveTier = {
   episode: string;
+  // the act a player's rank is from, absent on plain tiers
+  act?: string;
   tier: number;
   tierName?: string;
   icon?: string;
 };
+
+export type CustomGameData = {
+  partyId: string;
+  cheats: boolean;
+  tournamentMode: boolean;
+  useBots: boolean;
+  lobby: MatchData;
+};
+
+export type CustomGameTeam =
+  | "teamOne"
+  | "teamTwo"
+  | "teamSpectate"
+  | "teamOneCoaches"
+  | "teamTwoCoaches";
+
+export type ClientState =
+  | "offline"
+  | "menus"
+  | "queue"
+  | "pregame"
+  | "ingame"
+  | "postGame";
+
+// emitted under "match-event"
+export type MatchEvent =
+  | { type: "stateChanged"; data: { from: ClientState; to: ClientState } }
+  | { type: "matchSkeleton"; data: MatchData }
+  | { type: "playerEnriched"; data: PlayerUpdate }
+  | { type: "matchLoaded"; data: MatchData }
+  | { type: "matchUpdated"; data: MatchData }
+  | { type: "avoidAlert"; data: AvoidAlert };
+
+// milliseconds
+export type PollIntervals = {
+  offline: number;
+  menus: number;
+  queue: number;
+  pregame: number;
+  ingame: number;
+  postGame: number;
+};
+
+export type CacheEndpoint = "names" | "mmr";
+
+export type MatchResult = "win" | "loss";
+
+export type EncounterSummary = {
+  timesWith: number;
+  timesAgainst: number;
+  lastSeen: string;
+  lastResult?: MatchResult;
+};
+
+export type Encounter = {
+  matchId: string;
+  map: string;
+  mode: string;
+  seenAt: string;
+  // null if they were incognito
+  name: string | null;
+  team: string;
+  agent: string | null;
+  rank: string | null;
+  partyId: string;
+  won: boolean | null;
+};
+
+export type PlayerNote = {
+  puuid: string;
+  note: string;
+  tags: string[];
+  avoid: boolean;
+  updatedAt: string;
+};
+
+export type AvoidAlert = {
+  matchId: string;
+  name: string;
+  note: PlayerNote;
+};
+
+export type NameRecord = {
+  name: string;
+  firstSeen: string;
+  lastSeen: string;
+};
+
+export type ExportFormat = "json" | "csv" | "markdown";
+
+export type Availability = "online" | "away" | "busy" | "mobile" | "offline";
+
+export type FriendStatus = {
+  puuid: string;
+  name: string;
+  availability: Availability;
+  // offline unless they're playing Valorant, even if they're online in another game
+  state: ClientState;
+  // map display name, while they're in a match
+  map: string | null;
+  mode: string | null;
+  // rounds won by each team from the friend's side, only while they're ingame
+  score: { ally: number; enemy: number } | null;
+  partySize: number | null;
+  // their rank this act, as their presence shows it
+  rank: CompetitiveTier | null;
+};
+
+// emitted under "friend-changed" while friend notifications are on
+export type FriendChange = {
+  puuid: string;
+  name: string;
+  from: ClientState;
+  to: ClientState;
+  // the map they're playing or just left
+  map: string | null;
+};
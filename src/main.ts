@@ -7,6 +7,7 @@ import App from "@/App.vue";
 import LoginView from "@/routes/LoginView.vue";
 import PregameView from "@/routes/PregameView.vue";
 import IngameView from "@/routes/IngameView.vue";
+import FriendsView from "@/routes/FriendsView.vue";
 
 import "@/styles.css";
 
@@ -14,6 +15,7 @@ const routes = [
   { path: "/", component: LoginView },
   { path: "/pregame", component: PregameView },
   { path: "/ingame", component: IngameView },
+  { path: "/friends", component: FriendsView },
 ];
 
 const router = createRouter({